@@ -1,7 +1,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
 // Global instance ID for this process
@@ -49,6 +49,220 @@ mod tests {
         assert!(dir.to_string_lossy().contains("instance-"));
         assert!(dir.to_string_lossy().contains(instance_id()));
     }
+
+    #[test]
+    fn test_syntax_budgets_default_and_clamp() {
+        let config: Config = serde_json::from_str(r#"{"theme":"dark"}"#).unwrap();
+        assert_eq!(config.syntax_budgets, SyntaxBudgets::default());
+
+        let huge = SyntaxBudgets {
+            file_max_lines: usize::MAX,
+            diff_max_bytes: usize::MAX,
+            ..SyntaxBudgets::default()
+        }
+        .clamped();
+        assert_eq!(huge.file_max_lines, SyntaxBudgets::HARD_MAX.file_max_lines);
+        assert_eq!(huge.diff_max_bytes, SyntaxBudgets::HARD_MAX.diff_max_bytes);
+        assert_eq!(huge.file_max_bytes, SyntaxBudgets::default().file_max_bytes);
+        // The file line budget can be raised; diffs stop at what they draw
+        assert!(huge.file_max_lines > SyntaxBudgets::default().file_max_lines);
+        assert_eq!(
+            SyntaxBudgets::HARD_MAX.diff_max_lines,
+            crate::MAX_DIFF_VIEW_RENDER_LINES
+        );
+    }
+
+    #[test]
+    fn test_file_load_limits_default_and_clamp() {
+        let config: Config = serde_json::from_str(
+            r#"{"theme":"dark","file_load_limits":{"inline_webview_max_bytes":8000000}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.file_load_limits.inline_webview_max_bytes, 8_000_000);
+        assert_eq!(
+            config.file_load_limits.full_text_max_bytes,
+            FileLoadLimits::default().full_text_max_bytes
+        );
+
+        let huge = FileLoadLimits {
+            full_text_max_bytes: u64::MAX,
+            ..FileLoadLimits::default()
+        }
+        .clamped();
+        assert_eq!(huge.full_text_max_bytes, FileLoadLimits::HARD_MAX.full_text_max_bytes);
+        assert_eq!(huge.preview_max_lines, FileLoadLimits::default().preview_max_lines);
+    }
+
+    #[test]
+    fn test_pinned_repo_round_trip() {
+        let tab = WorkspaceTabConfig {
+            dir: "/work/mono/services/api".to_string(),
+            repo_dir: Some("/work/mono/vendor/lib".to_string()),
+            repo_pinned: true,
+            startup_command: None,
+            pinned: false,
+        };
+        let json = serde_json::to_string(&tab).unwrap();
+        assert!(json.contains("\"repo_pinned\":true"));
+        let restored: WorkspaceTabConfig = serde_json::from_str(&json).unwrap();
+        assert!(restored.repo_pinned);
+
+        // Unpinned tabs keep the old on-disk shape
+        let unpinned = WorkspaceTabConfig {
+            repo_pinned: false,
+            ..tab
+        };
+        let json = serde_json::to_string(&unpinned).unwrap();
+        assert!(!json.contains("repo_pinned"));
+        let restored: WorkspaceTabConfig =
+            serde_json::from_str(r#"{"dir":"/work","repo_dir":"/work"}"#).unwrap();
+        assert!(!restored.repo_pinned);
+    }
+
+    #[test]
+    fn test_setting_defaults() {
+        // Only `theme` is required; the rest fall back to their defaults
+        let config: Config = serde_json::from_str(r#"{"theme":"dark"}"#).unwrap();
+        let saved = serde_json::to_value(&config).unwrap();
+        let defaults = [
+            ("explorer_sort", serde_json::json!("name")),
+            ("explorer_dirs_first", serde_json::json!(true)),
+            ("show_diff_ruler", serde_json::json!(false)),
+            ("show_whitespace", serde_json::json!(false)),
+            ("word_diff", serde_json::json!("words")),
+            ("confirm_paste", serde_json::json!(true)),
+            ("confirm_clear", serde_json::json!(false)),
+            ("clipboard_history", serde_json::json!(false)),
+            ("commit_type", serde_json::json!(null)),
+            ("commit_template", serde_json::json!(null)),
+            ("last_folder_dir", serde_json::json!(null)),
+            ("tab_bar_position", serde_json::json!("top")),
+            ("tab_overflow", serde_json::json!("scroll")),
+            ("new_tab_dir", serde_json::json!("current")),
+            ("pause_when_unfocused", serde_json::json!(true)),
+            ("git_poll_min_interval_ms", serde_json::json!(0)),
+            ("console_timestamps", serde_json::json!("clock")),
+            ("console_clock_format", serde_json::json!("%H:%M:%S")),
+            ("console_on_error", serde_json::json!("expand")),
+            ("workspace_save_debounce_ms", serde_json::json!(1500)),
+            (
+                "view_source",
+                serde_json::json!({"markdown": false, "html": false}),
+            ),
+        ];
+        for (key, value) in defaults {
+            assert_eq!(saved[key], value, "default of {}", key);
+        }
+    }
+
+    #[test]
+    fn test_settings_round_trip() {
+        let settings = serde_json::json!({
+            "theme": "light",
+            "explorer_sort": "modified",
+            "explorer_dirs_first": false,
+            "show_diff_ruler": true,
+            "show_whitespace": true,
+            "word_diff": "identifiers",
+            "confirm_paste": false,
+            "confirm_clear": true,
+            "clipboard_history": true,
+            "commit_type": "fix",
+            "commit_template": "# why\nfix: ",
+            "last_folder_dir": "/home/me/src",
+            "tab_bar_position": "bottom",
+            "tab_overflow": "menu",
+            "new_tab_dir": "workspace",
+            "pause_when_unfocused": false,
+            "git_poll_min_interval_ms": 60000,
+            "console_timestamps": "elapsed",
+            "console_clock_format": "%H:%M",
+            "console_on_error": "flash",
+            "workspace_save_debounce_ms": 0,
+            "view_source": {"markdown": false, "html": true},
+        });
+        let config: Config = serde_json::from_value(settings.clone()).unwrap();
+        assert_eq!(config.console_on_error, ConsoleOnError::Flash);
+        assert_eq!(config.last_folder_dir, Some(PathBuf::from("/home/me/src")));
+        let saved = serde_json::to_value(&config).unwrap();
+        for (key, value) in settings.as_object().unwrap() {
+            assert_eq!(&saved[key], value, "{} after saving", key);
+        }
+    }
+
+    #[test]
+    fn test_cycled_settings_wrap_around() {
+        assert_eq!(ConsoleOnError::Never.next(), ConsoleOnError::Expand);
+        assert_eq!(ExplorerSort::Size.next(), ExplorerSort::Name);
+    }
+
+    #[test]
+    fn test_diff_color_overrides() {
+        use crate::theme::AppTheme;
+
+        let config: Config = serde_json::from_str(r#"{"theme":"dark"}"#).unwrap();
+        assert_eq!(config.diff_colors.apply(&AppTheme::Light), AppTheme::Light.diff_colors());
+
+        let config: Config = serde_json::from_str(
+            r##"{"theme":"dark","diff_colors":{"light":{"add_bg":"#e6ffec","del_bg":"bad"}}}"##,
+        )
+        .unwrap();
+        let light = config.diff_colors.apply(&AppTheme::Light);
+        assert_eq!(light.add_bg, iced::Color::from_rgb8(0xe6, 0xff, 0xec));
+        assert_eq!(light.del_bg, AppTheme::Light.diff_del_bg());
+        assert_eq!(config.diff_colors.apply(&AppTheme::Dark), AppTheme::Dark.diff_colors());
+    }
+
+    #[test]
+    fn test_open_as_config() {
+        let config: Config = serde_json::from_str(r#"{"theme":"dark"}"#).unwrap();
+        assert!(config.open_as.is_empty());
+        let config: Config = serde_json::from_str(
+            r#"{"theme":"dark","open_as":{"html":"source",".LOG":"tail","md":"preview"}}"#,
+        )
+        .unwrap();
+        let mode = |path: &str| open_as_entry(&config.open_as, Path::new(path)).map(|e| e.1);
+        assert_eq!(mode("site/index.html"), Some(OpenMode::Source));
+        assert_eq!(mode("server.log"), Some(OpenMode::Tail));
+        assert_eq!(mode("README.MD"), Some(OpenMode::Preview));
+        assert_eq!(mode("notes.txt"), None);
+        assert_eq!(mode("Makefile"), None);
+    }
+
+    #[test]
+    fn test_markdown_render_config() {
+        let config: Config = serde_json::from_str(r#"{"theme":"dark"}"#).unwrap();
+        assert_eq!(config.markdown, MarkdownRender::default());
+        assert!(config.markdown.allow_html);
+        let config: Config = serde_json::from_str(
+            r#"{"theme":"dark","markdown":{"css_path":"/docs/site.css","allow_html":false}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.markdown.css_path, Some(PathBuf::from("/docs/site.css")));
+        assert!(!config.markdown.allow_html);
+        assert!(!config.markdown.scroll_sync);
+        let config: Config =
+            serde_json::from_str(r#"{"theme":"dark","markdown":{"scroll_sync":true}}"#).unwrap();
+        assert!(config.markdown.scroll_sync);
+        assert!(config.markdown.allow_html);
+    }
+
+    #[test]
+    fn test_stt_config_round_trips_in_every_build() {
+        let config: Config = serde_json::from_str(
+            r#"{"theme":"dark","stt_enabled":true,"stt_model_path":"/models/base.en.bin"}"#,
+        )
+        .unwrap();
+        let saved: serde_json::Value = serde_json::to_value(&config).unwrap();
+        assert_eq!(saved["stt_enabled"], true);
+        assert_eq!(saved["stt_model_path"], "/models/base.en.bin");
+
+        // Settings this build doesn't know about are ignored rather than failing the load
+        let config: Config =
+            serde_json::from_str(r#"{"theme":"dark","stt_language":"en"}"#).unwrap();
+        assert!(!config.stt_enabled);
+    }
+
 }
 
 /// Clean up this instance's config directory on exit
@@ -122,11 +336,46 @@ fn default_console_expanded() -> bool {
     true
 }
 
+fn default_explorer_dirs_first() -> bool {
+    true
+}
+
+fn default_show_status_bar() -> bool {
+    true
+}
+
+fn default_terminal_links() -> bool {
+    true
+}
+
+fn default_skip_empty_diffs() -> bool {
+    true
+}
+
+fn default_confirm_paste() -> bool {
+    true
+}
+
+fn default_pause_when_unfocused() -> bool {
+    true
+}
+
+fn default_console_clock_format() -> String {
+    "%H:%M:%S".to_string()
+}
+
 fn default_log_server_enabled() -> bool {
     false
 }
 
-#[cfg(feature = "stt")]
+fn default_workspace_save_debounce_ms() -> u64 {
+    1500
+}
+
+fn default_transcript_max_bytes() -> u64 {
+    5 * 1024 * 1024
+}
+
 fn default_stt_enabled() -> bool {
     false
 }
@@ -145,25 +394,554 @@ pub struct Config {
     // Legacy field for migration
     #[serde(default)]
     pub font_size: Option<f32>,
+    /// "dark", "light", or "system" to follow the desktop appearance
     pub theme: String,
     #[serde(default)]
     pub show_hidden: bool,
+    /// Show files matched by .gitignore / core.excludesfile in the explorer
+    #[serde(default)]
+    pub show_ignored: bool,
+    /// Explorer order within a directory: "name", "modified" (newest first) or "size"
+    #[serde(default)]
+    pub explorer_sort: ExplorerSort,
+    /// List directories ahead of files in the explorer, whatever the sort
+    #[serde(default = "default_explorer_dirs_first")]
+    pub explorer_dirs_first: bool,
+    /// Single-click previews a file and double-click pins it (default: click opens)
+    #[serde(default)]
+    pub preview_on_click: bool,
+    /// Minimap strip beside the file and diff viewers (off by default; costs render time)
+    #[serde(default)]
+    pub show_minimap: bool,
+    /// Strip beside the diff viewer marking where lines were added and deleted, shown
+    /// while the minimap is off (off by default)
+    #[serde(default)]
+    pub show_diff_ruler: bool,
+    /// Faint markers for tabs and trailing spaces in the file and diff viewers
+    #[serde(default)]
+    pub show_whitespace: bool,
+    /// j/k in the diff view pass over files whose diff is empty (e.g. mode-only changes)
+    #[serde(default = "default_skip_empty_diffs")]
+    pub skip_empty_diffs: bool,
+    #[serde(default)]
+    pub diff_gutter: DiffGutter,
+    /// How changed lines are split up for the inline word highlights in diffs
+    #[serde(default)]
+    pub word_diff: WordDiffMode,
+    /// Cmd+Shift+O lists the URLs printed in the active terminal to open in the browser.
+    /// A picker rather than clickable text: the terminal widget doesn't expose OSC 8
+    /// targets or which cell is under the mouse.
+    #[serde(default = "default_terminal_links")]
+    pub terminal_links: bool,
+    /// Ask before pasting multi-line text, text ending in a newline or `sudo rm`
+    /// into a terminal that hasn't enabled bracketed paste
+    #[serde(default = "default_confirm_paste")]
+    pub confirm_paste: bool,
+    /// Ask before Cmd+K clears a terminal or the console's output is cleared
+    #[serde(default)]
+    pub confirm_clear: bool,
+    /// Remember the last few copies (Cmd+C) for re-copying from Cmd+Shift+V. Off by
+    /// default since it keeps clipboard contents in memory; never written to disk.
+    #[serde(default)]
+    pub clipboard_history: bool,
+    /// Conventional Commits type last picked in the commit box (e.g. `"feat"`)
+    #[serde(default)]
+    pub commit_type: Option<String>,
+    /// Commit message template used when the repo has no `.gitmessage` and git has no
+    /// `commit.template`; `#` lines are left out of the inserted message
+    #[serde(default)]
+    pub commit_template: Option<String>,
+    /// Command for "Difftool" in the diff header, run in the repo root with the
+    /// diff's revisions and `-- <path>` appended (default: `git difftool`, with
+    /// `$DIFFTOOL` as the tool when set)
+    #[serde(default)]
+    pub difftool_command: Option<String>,
+    /// Where "Open Folder" starts browsing: the parent of the last folder opened
+    #[serde(default)]
+    pub last_folder_dir: Option<PathBuf>,
+    /// Branch, directory and process indicators along the bottom of the window
+    #[serde(default = "default_show_status_bar")]
+    pub show_status_bar: bool,
+    /// Tab bar above the content ("top") or beside the workspace bar ("bottom")
+    #[serde(default)]
+    pub tab_bar_position: TabBarPosition,
+    /// Tabs that don't fit: scroll the bar ("scroll") or list them in a menu ("menu")
+    #[serde(default)]
+    pub tab_overflow: TabOverflow,
+    /// Where new tabs start: "current" (the active tab's directory), "repo" (its
+    /// repository root) or "workspace" (the workspace folder)
+    #[serde(default)]
+    pub new_tab_dir: NewTabDir,
+    /// Live memory and cache figures in a corner panel (Cmd+Shift+P)
+    #[serde(default)]
+    pub show_perf_overlay: bool,
+    /// Stop the attention pulse and other cosmetic timers, and poll git at the idle
+    /// cadence, while the window doesn't have focus
+    #[serde(default = "default_pause_when_unfocused")]
+    pub pause_when_unfocused: bool,
+    /// Shortest wait between git status polls of a tab, on top of the adaptive
+    /// cadence; raise it for repos where `git status` is slow (0 keeps the cadence)
+    #[serde(default)]
+    pub git_poll_min_interval_ms: u64,
     #[serde(default = "default_console_height")]
     pub console_height: f32,
     #[serde(default = "default_console_expanded")]
     pub console_expanded: bool,
+    #[serde(default)]
+    pub console_timestamps: ConsoleTimestamps,
+    /// What a run command exiting with an error does to a collapsed or hidden console
+    #[serde(default)]
+    pub console_on_error: ConsoleOnError,
+    /// strftime format for `"clock"` console timestamps
+    #[serde(default = "default_console_clock_format")]
+    pub console_clock_format: String,
     #[serde(default = "default_log_server_enabled")]
     pub log_server_enabled: bool,
-    #[cfg(feature = "stt")]
+    /// Also mirror bottom panel terminals and console output to the log server
+    #[serde(default)]
+    pub log_server_include_panels: bool,
+    /// How long workspace changes wait before being written to workspaces.json;
+    /// 0 writes on the next tick
+    #[serde(default = "default_workspace_save_debounce_ms")]
+    pub workspace_save_debounce_ms: u64,
+    /// Keep a plain-text transcript of each agent tab under `~/.config/gitterm/transcripts`
+    #[serde(default)]
+    pub transcripts_enabled: bool,
+    /// Size at which a transcript is rotated to `<name>.log.1`
+    #[serde(default = "default_transcript_max_bytes")]
+    pub transcript_max_bytes: u64,
+    /// Speech-to-text settings. Only builds with the `stt` feature use them, but every
+    /// build keeps them so switching builds doesn't drop them on the next save.
     #[serde(default = "default_stt_enabled")]
     pub stt_enabled: bool,
-    #[cfg(feature = "stt")]
     #[serde(default)]
     pub stt_model_path: Option<String>,
     #[serde(default = "default_agent_presets")]
     pub agent_presets: Vec<AgentPreset>,
     #[serde(default)]
     pub quick_commands: Vec<QuickCommand>,
+    #[serde(default)]
+    pub syntax_budgets: SyntaxBudgets,
+    #[serde(default)]
+    pub file_load_limits: FileLoadLimits,
+    #[serde(default)]
+    pub diff_colors: DiffColorOverrides,
+    #[serde(default)]
+    pub view_source: ViewSource,
+    /// How files open by extension, e.g. `{"md": "preview", "log": "tail"}`. Wins over
+    /// `view_source`; other extensions keep the usual choice.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub open_as: HashMap<String, OpenMode>,
+    #[serde(default)]
+    pub markdown: MarkdownRender,
+}
+
+/// How Markdown previews are rendered, here and in "View in Browser".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarkdownRender {
+    /// CSS file applied after the built-in styles, to match a project's docs
+    pub css_path: Option<PathBuf>,
+    /// Keep HTML written in the Markdown (`<details>`, `<img>`); off shows it as text
+    pub allow_html: bool,
+    /// Land on the same heading when switching between the source and the preview
+    pub scroll_sync: bool,
+}
+
+impl Default for MarkdownRender {
+    fn default() -> Self {
+        Self {
+            css_path: None,
+            allow_html: true,
+            scroll_sync: false,
+        }
+    }
+}
+
+/// Markdown and HTML files shown as highlighted source rather than rendered,
+/// switched with the file viewer's View Source / View Rendered button.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewSource {
+    pub markdown: bool,
+    pub html: bool,
+}
+
+/// File viewer mode for one extension in `Config::open_as`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OpenMode {
+    /// Rendered Markdown/HTML; other files show as text
+    Preview,
+    /// Highlighted source, Markdown/HTML included
+    Source,
+    /// Source scrolled to the end, following it as the file grows
+    Tail,
+}
+
+/// The `open_as` entry for `path`'s extension, ignoring case and a leading dot
+pub fn open_as_entry<'a>(
+    open_as: &'a HashMap<String, OpenMode>,
+    path: &Path,
+) -> Option<(&'a String, OpenMode)> {
+    let ext = path.extension()?.to_str()?;
+    open_as
+        .iter()
+        .find(|(key, _)| key.trim_start_matches('.').eq_ignore_ascii_case(ext))
+        .map(|(key, mode)| (key, *mode))
+}
+
+/// Hex colors (`"#rrggbb"` or `"#rrggbbaa"`) replacing the diff backgrounds of either
+/// theme, e.g. `{"light": {"add_bg": "#e6ffec"}}`. Unset or unreadable entries keep
+/// the theme's color.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiffColorOverrides {
+    pub dark: DiffColorSet,
+    pub light: DiffColorSet,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiffColorSet {
+    pub add_bg: Option<String>,
+    pub del_bg: Option<String>,
+    pub add_highlight: Option<String>,
+    pub del_highlight: Option<String>,
+}
+
+impl DiffColorOverrides {
+    /// The theme's diff colors with this config's replacements for it applied.
+    pub fn apply(&self, theme: &crate::theme::AppTheme) -> crate::theme::DiffColors {
+        use crate::theme::{parse_hex_color, AppTheme};
+
+        let set = match theme {
+            AppTheme::Dark => &self.dark,
+            AppTheme::Light => &self.light,
+        };
+        let base = theme.diff_colors();
+        let pick = |value: &Option<String>, fallback| {
+            value.as_deref().and_then(parse_hex_color).unwrap_or(fallback)
+        };
+        crate::theme::DiffColors {
+            add_bg: pick(&set.add_bg, base.add_bg),
+            del_bg: pick(&set.del_bg, base.del_bg),
+            add_highlight: pick(&set.add_highlight, base.add_highlight),
+            del_highlight: pick(&set.del_highlight, base.del_highlight),
+        }
+    }
+}
+
+/// How much of a file or diff gets syntax highlighted. Raise these on fast machines
+/// to highlight more of large files; values above `HARD_MAX` are clamped. The file
+/// viewer draws as many lines as `file_max_lines` allows (at least 1200); diffs stop
+/// at 1200 drawn lines, which caps `diff_max_lines`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyntaxBudgets {
+    pub file_max_bytes: usize,
+    pub file_max_lines: usize,
+    pub file_max_segments: usize,
+    pub diff_max_bytes: usize,
+    pub diff_max_lines: usize,
+    pub diff_max_segments: usize,
+}
+
+impl Default for SyntaxBudgets {
+    fn default() -> Self {
+        Self {
+            file_max_bytes: 96 * 1024,
+            file_max_lines: 1200,
+            file_max_segments: 8000,
+            diff_max_bytes: 768 * 1024,
+            diff_max_lines: 900,
+            diff_max_segments: 9000,
+        }
+    }
+}
+
+impl SyntaxBudgets {
+    /// Upper bounds that keep highlighted output from growing without limit.
+    pub const HARD_MAX: Self = Self {
+        file_max_bytes: 4 * 1024 * 1024,
+        file_max_lines: 5000,
+        file_max_segments: 200_000,
+        diff_max_bytes: 8 * 1024 * 1024,
+        diff_max_lines: crate::MAX_DIFF_VIEW_RENDER_LINES,
+        diff_max_segments: 200_000,
+    };
+
+    pub fn clamped(self) -> Self {
+        let max = Self::HARD_MAX;
+        Self {
+            file_max_bytes: self.file_max_bytes.min(max.file_max_bytes),
+            file_max_lines: self.file_max_lines.min(max.file_max_lines),
+            file_max_segments: self.file_max_segments.min(max.file_max_segments),
+            diff_max_bytes: self.diff_max_bytes.min(max.diff_max_bytes),
+            diff_max_lines: self.diff_max_lines.min(max.diff_max_lines),
+            diff_max_segments: self.diff_max_segments.min(max.diff_max_segments),
+        }
+    }
+}
+
+/// Size limits for the file viewer. Markdown/HTML (and Excalidraw) files above
+/// `inline_webview_max_bytes` aren't rendered inline; text files above
+/// `full_text_max_bytes` show only their first `preview_max_lines` lines, read from at
+/// most `preview_max_bytes`. Values above `HARD_MAX` are clamped.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FileLoadLimits {
+    pub inline_webview_max_bytes: u64,
+    pub full_text_max_bytes: u64,
+    pub preview_max_bytes: usize,
+    pub preview_max_lines: usize,
+}
+
+impl Default for FileLoadLimits {
+    fn default() -> Self {
+        Self {
+            inline_webview_max_bytes: 1_500_000,
+            full_text_max_bytes: 1_000_000,
+            preview_max_bytes: 256 * 1024,
+            preview_max_lines: 2000,
+        }
+    }
+}
+
+impl FileLoadLimits {
+    /// Past these the viewer spends seconds loading or rendering a single file.
+    pub const HARD_MAX: Self = Self {
+        inline_webview_max_bytes: 20 * 1024 * 1024,
+        full_text_max_bytes: 50 * 1024 * 1024,
+        preview_max_bytes: 4 * 1024 * 1024,
+        preview_max_lines: 50_000,
+    };
+
+    pub fn clamped(self) -> Self {
+        let max = Self::HARD_MAX;
+        Self {
+            inline_webview_max_bytes: self
+                .inline_webview_max_bytes
+                .min(max.inline_webview_max_bytes),
+            full_text_max_bytes: self.full_text_max_bytes.min(max.full_text_max_bytes),
+            preview_max_bytes: self.preview_max_bytes.min(max.preview_max_bytes),
+            preview_max_lines: self.preview_max_lines.min(max.preview_max_lines),
+        }
+    }
+}
+
+/// Prefix on console output lines: wall-clock time, time since the process started
+/// (handy for profiling startup), or nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleTimestamps {
+    #[default]
+    Clock,
+    Elapsed,
+    Off,
+}
+
+impl ConsoleTimestamps {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Clock => Self::Elapsed,
+            Self::Elapsed => Self::Off,
+            Self::Off => Self::Clock,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Clock => "clock",
+            Self::Elapsed => "elapsed",
+            Self::Off => "off",
+        }
+    }
+}
+
+/// Where the tab bar sits: above the terminal, or at the bottom just above the
+/// workspace bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TabBarPosition {
+    #[default]
+    Top,
+    Bottom,
+}
+
+impl TabBarPosition {
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Top => Self::Bottom,
+            Self::Bottom => Self::Top,
+        }
+    }
+}
+
+/// What the tab bar does with more tabs than fit: scroll sideways, or show the ones
+/// around the active tab and list everything in a searchable menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TabOverflow {
+    #[default]
+    Scroll,
+    Menu,
+}
+
+impl TabOverflow {
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Scroll => Self::Menu,
+            Self::Menu => Self::Scroll,
+        }
+    }
+}
+
+/// Where new agent and plain tabs start: the active tab's current directory, its
+/// repository root, or the workspace folder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NewTabDir {
+    #[default]
+    Current,
+    Repo,
+    Workspace,
+}
+
+/// How the explorer orders a directory's entries: by name, most recently modified
+/// first, or largest first. Ties fall back to the name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExplorerSort {
+    #[default]
+    Name,
+    Modified,
+    Size,
+}
+
+impl ExplorerSort {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Name => Self::Modified,
+            Self::Modified => Self::Size,
+            Self::Size => Self::Name,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Modified => "recent",
+            Self::Size => "size",
+        }
+    }
+}
+
+/// What the console does when its command exits with an error: expand to show the
+/// output, pulse the Console tab in the attention color, or nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleOnError {
+    #[default]
+    Expand,
+    Flash,
+    Never,
+}
+
+impl ConsoleOnError {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Expand => Self::Flash,
+            Self::Flash => Self::Never,
+            Self::Never => Self::Expand,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Expand => "expand",
+            Self::Flash => "flash",
+            Self::Never => "never",
+        }
+    }
+}
+
+/// How the console starts a workspace's run command: through a login shell so
+/// profile-managed PATHs (nvm, rbenv, ...) apply, through a plain shell that skips
+/// the profiles, or exec'd directly without a shell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleShell {
+    #[default]
+    Login,
+    Plain,
+    Direct,
+}
+
+impl ConsoleShell {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Login => Self::Plain,
+            Self::Plain => Self::Direct,
+            Self::Direct => Self::Login,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Login => "login",
+            Self::Plain => "plain",
+            Self::Direct => "direct",
+        }
+    }
+
+    pub fn is_login(&self) -> bool {
+        *self == Self::Login
+    }
+}
+
+/// What the diff gutter shows: old and new line numbers, just the new side,
+/// offsets from the start of each hunk, or a single "old→new" column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffGutter {
+    #[default]
+    Both,
+    New,
+    Hunk,
+    Combined,
+}
+
+impl DiffGutter {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Both => Self::New,
+            Self::New => Self::Hunk,
+            Self::Hunk => Self::Combined,
+            Self::Combined => Self::Both,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Both => "old/new",
+            Self::New => "new",
+            Self::Hunk => "hunk",
+            Self::Combined => "old\u{2192}new",
+        }
+    }
+}
+
+/// Tokens compared for inline word highlights: whitespace-separated words, code
+/// identifiers (`foo_bar`, `camelCase`) apart from the punctuation around them, or
+/// single graphemes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WordDiffMode {
+    #[default]
+    Words,
+    Identifiers,
+    Graphemes,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -260,15 +1038,51 @@ impl Default for Config {
             font_size: None,
             theme: "dark".to_string(),
             show_hidden: false,
+            show_ignored: false,
+            explorer_sort: ExplorerSort::Name,
+            explorer_dirs_first: true,
+            preview_on_click: false,
+            show_minimap: false,
+            show_diff_ruler: false,
+            show_whitespace: false,
+            skip_empty_diffs: true,
+            diff_gutter: DiffGutter::Both,
+            word_diff: WordDiffMode::Words,
+            terminal_links: true,
+            confirm_paste: true,
+            confirm_clear: false,
+            clipboard_history: false,
+            commit_type: None,
+            commit_template: None,
+            difftool_command: None,
+            last_folder_dir: None,
+            show_status_bar: true,
+            tab_bar_position: TabBarPosition::Top,
+            tab_overflow: TabOverflow::Scroll,
+            new_tab_dir: NewTabDir::Current,
+            show_perf_overlay: false,
+            pause_when_unfocused: true,
+            git_poll_min_interval_ms: 0,
             console_height: 200.0,
             console_expanded: true,
+            console_timestamps: ConsoleTimestamps::Clock,
+            console_on_error: ConsoleOnError::Expand,
+            console_clock_format: default_console_clock_format(),
             log_server_enabled: false,
-            #[cfg(feature = "stt")]
+            log_server_include_panels: false,
+            workspace_save_debounce_ms: default_workspace_save_debounce_ms(),
+            transcripts_enabled: false,
+            transcript_max_bytes: default_transcript_max_bytes(),
             stt_enabled: true,
-            #[cfg(feature = "stt")]
             stt_model_path: None,
             agent_presets: default_agent_presets(),
             quick_commands: Vec::new(),
+            syntax_budgets: SyntaxBudgets::default(),
+            file_load_limits: FileLoadLimits::default(),
+            diff_colors: DiffColorOverrides::default(),
+            view_source: ViewSource::default(),
+            open_as: HashMap::new(),
+            markdown: MarkdownRender::default(),
         }
     }
 }
@@ -290,14 +1104,8 @@ impl Config {
         Self::default()
     }
 
-    pub fn save(&self) {
-        let path = Self::config_path();
-        if let Some(parent) = path.parent() {
-            let _ = std::fs::create_dir_all(parent);
-        }
-        if let Ok(json) = serde_json::to_string_pretty(self) {
-            let _ = std::fs::write(path, json);
-        }
+    pub fn save(&self) -> std::io::Result<()> {
+        write_json(&Self::config_path(), self)
     }
 }
 
@@ -317,13 +1125,43 @@ pub struct WorkspaceConfig {
     pub tabs: Vec<WorkspaceTabConfig>,
     #[serde(default)]
     pub run_command: Option<String>,
+    /// How `run_command` is started; a login shell unless profiles are too slow
+    #[serde(default, skip_serializing_if = "ConsoleShell::is_login")]
+    pub console_shell: ConsoleShell,
     #[serde(default)]
     pub bottom_terminals: Vec<BottomTerminalConfig>,
+    /// Index of the bottom terminal shown when the workspace was saved; None for the Console
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_bottom_terminal: Option<usize>,
     /// Environment variables to inject into all terminal sessions in this workspace.
     /// Edit workspaces.json to add any vars without recompiling, e.g.:
-    /// "env": { "LINEAR_WORKSPACE": "truinsights", "LINEAR_TEAM": "TRU", "GH_TOKEN": "..." }
+    /// "env": { "LINEAR_WORKSPACE": "truinsights", "LINEAR_TEAM": "TRU", "GH_TOKEN": "${GH}" }
+    /// `${VAR}` resolves from GitTerm's environment when a terminal is created, so secrets
+    /// needn't be stored here. Only the braced form expands: `$VAR` and any other `$` stay
+    /// literal, and `$${` writes `${`. Changes apply to newly created terminals.
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub env: HashMap<String, String>,
+    /// Files pinned to the top of the Files sidebar, relative to `dir` when inside it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pinned_files: Vec<String>,
+    /// Identity for commits made from the commit box in this workspace, in place of
+    /// git's `user.name`/`user.email`, e.g.:
+    /// "git_author": { "name": "Ada", "email": "ada@client.com" }
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_author: Option<GitAuthor>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitAuthor {
+    pub name: String,
+    pub email: String,
+}
+
+impl GitAuthor {
+    /// "Name <email>", as git prints an identity.
+    pub fn label(&self) -> String {
+        format!("{} <{}>", self.name, self.email)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -331,13 +1169,22 @@ pub struct WorkspaceTabConfig {
     pub dir: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub repo_dir: Option<String>,
+    /// `repo_dir` was chosen by hand and isn't re-discovered when the shell `cd`s
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub repo_pinned: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub startup_command: Option<String>,
+    /// Pinned tabs sit at the left and are skipped by bulk closes
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BottomTerminalConfig {
     pub dir: String,
+    /// Custom tab name; the shell's title is shown when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
 }
 
 impl WorkspacesFile {
@@ -355,13 +1202,15 @@ impl WorkspacesFile {
         }
     }
 
-    pub fn save(&self) {
-        let path = Self::file_path();
-        if let Some(parent) = path.parent() {
-            let _ = std::fs::create_dir_all(parent);
-        }
-        if let Ok(json) = serde_json::to_string_pretty(self) {
-            let _ = std::fs::write(path, json);
-        }
+    pub fn save(&self) -> std::io::Result<()> {
+        write_json(&Self::file_path(), self)
+    }
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
     }
+    let json = serde_json::to_string_pretty(value).map_err(std::io::Error::other)?;
+    std::fs::write(path, json)
 }
\ No newline at end of file
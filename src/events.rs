@@ -4,4 +4,16 @@ pub enum SidebarMode {
     Files,
     Claude,
     Agent,
+}
+
+impl SidebarMode {
+    /// The mode to the right in the sidebar's tab strip, wrapping around.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Git => Self::Files,
+            Self::Files => Self::Claude,
+            Self::Claude => Self::Agent,
+            Self::Agent => Self::Git,
+        }
+    }
 }
\ No newline at end of file
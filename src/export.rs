@@ -0,0 +1,217 @@
+//! Standalone HTML copies of the diff and file views for pasting into PR
+//! descriptions or review notes. Colors come from the current `AppTheme` so an
+//! export looks like the view it was taken from.
+
+use crate::theme::{AppTheme, DiffColors};
+use crate::{ChangeType, DiffLine, DiffLineType, SyntaxHighlightLine, SyntaxHighlightSegment};
+use iced::Color;
+
+fn css(color: Color) -> String {
+    let [r, g, b, a] = color.into_rgba8();
+    if a == 255 {
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    } else {
+        format!("rgba({}, {}, {}, {:.2})", r, g, b, color.a)
+    }
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn span(text: &str, color: Color, background: Option<Color>) -> String {
+    match background {
+        Some(bg) => format!(
+            "<span style=\"color:{};background:{}\">{}</span>",
+            css(color),
+            css(bg),
+            html_escape(text)
+        ),
+        None => format!(
+            "<span style=\"color:{}\">{}</span>",
+            css(color),
+            html_escape(text)
+        ),
+    }
+}
+
+fn segments_html(segments: &[SyntaxHighlightSegment]) -> String {
+    segments
+        .iter()
+        .map(|segment| span(&segment.text, segment.color, None))
+        .collect()
+}
+
+fn document(title: &str, theme: &AppTheme, rows: String) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+         <style>\n\
+         body {{ margin: 0; padding: 16px; background: {bg}; color: {text}; }}\n\
+         h1 {{ font: 600 14px -apple-system, BlinkMacSystemFont, sans-serif; margin: 0 0 12px; }}\n\
+         table {{ border-collapse: collapse; width: 100%; \
+         font: 12px/1.5 Menlo, Monaco, Consolas, monospace; }}\n\
+         td {{ padding: 0 8px; white-space: pre; vertical-align: top; }}\n\
+         td.num {{ color: {muted}; text-align: right; user-select: none; width: 1%; }}\n\
+         </style>\n</head>\n<body>\n<h1>{title}</h1>\n<table>\n{rows}</table>\n</body>\n</html>\n",
+        title = html_escape(title),
+        bg = css(theme.bg_base()),
+        text = css(theme.text_primary()),
+        muted = css(theme.text_muted()),
+        rows = rows,
+    )
+}
+
+/// The diff as shown in the diff panel: line numbers, +/- gutters, word-level
+/// changes and syntax colors where they were computed.
+pub(crate) fn diff_html(
+    title: &str,
+    lines: &[DiffLine],
+    syntax: Option<&[Vec<SyntaxHighlightSegment>]>,
+    theme: &AppTheme,
+    colors: &DiffColors,
+) -> String {
+    let mut rows = String::new();
+    for (idx, line) in lines.iter().enumerate() {
+        let (line_color, bg) = match line.line_type {
+            DiffLineType::Addition => (theme.success(), Some(colors.add_bg)),
+            DiffLineType::Deletion => (theme.danger(), Some(colors.del_bg)),
+            DiffLineType::Header => (theme.accent(), None),
+            DiffLineType::Context => (theme.text_secondary(), None),
+        };
+        let row_style = bg
+            .map(|bg| format!(" style=\"background:{}\"", css(bg)))
+            .unwrap_or_default();
+        if line.line_type == DiffLineType::Header {
+            rows.push_str(&format!(
+                "<tr{}><td class=\"num\"></td><td class=\"num\"></td><td>{}</td></tr>\n",
+                row_style,
+                span(&line.content, line_color, None)
+            ));
+            continue;
+        }
+
+        let prefix = match line.line_type {
+            DiffLineType::Addition => "+",
+            DiffLineType::Deletion => "-",
+            _ => " ",
+        };
+        let segments = syntax
+            .and_then(|lines| lines.get(idx))
+            .filter(|segments| !segments.is_empty());
+        let content = if let Some(changes) = &line.inline_changes {
+            changes
+                .iter()
+                .map(|change| match (&line.line_type, &change.change_type) {
+                    (DiffLineType::Deletion, ChangeType::Delete) => span(
+                        &change.value,
+                        Color::WHITE,
+                        Some(colors.del_highlight),
+                    ),
+                    (DiffLineType::Addition, ChangeType::Insert) => span(
+                        &change.value,
+                        Color::WHITE,
+                        Some(colors.add_highlight),
+                    ),
+                    _ => span(&change.value, line_color, None),
+                })
+                .collect()
+        } else if let Some(segments) = segments {
+            segments_html(segments)
+        } else {
+            span(&line.content, line_color, None)
+        };
+        rows.push_str(&format!(
+            "<tr{}><td class=\"num\">{}</td><td class=\"num\">{}</td><td>{}{}</td></tr>\n",
+            row_style,
+            line.old_line_num.map(|n| n.to_string()).unwrap_or_default(),
+            line.new_line_num.map(|n| n.to_string()).unwrap_or_default(),
+            span(prefix, line_color, None),
+            content
+        ));
+    }
+    document(title, theme, rows)
+}
+
+/// A file with line numbers; lines past the highlighted range are plain text.
+pub(crate) fn file_html(
+    title: &str,
+    content: &str,
+    syntax: Option<&[SyntaxHighlightLine]>,
+    theme: &AppTheme,
+) -> String {
+    let mut rows = String::new();
+    for (idx, line) in content.lines().enumerate() {
+        let segments = syntax
+            .and_then(|lines| lines.get(idx))
+            .filter(|line| !line.segments.is_empty());
+        let cell = match segments {
+            Some(line) => segments_html(&line.segments),
+            None => span(line, theme.text_primary(), None),
+        };
+        rows.push_str(&format!(
+            "<tr><td class=\"num\">{}</td><td>{}</td></tr>\n",
+            idx + 1,
+            cell
+        ));
+    }
+    document(title, theme, rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_html_escapes_and_colors_lines() {
+        let lines = vec![
+            DiffLine {
+                content: "@@ -1,1 +1,1 @@".to_string(),
+                line_type: DiffLineType::Header,
+                old_line_num: None,
+                new_line_num: None,
+                inline_changes: None,
+            },
+            DiffLine {
+                content: "a < b".to_string(),
+                line_type: DiffLineType::Deletion,
+                old_line_num: Some(1),
+                new_line_num: None,
+                inline_changes: None,
+            },
+            DiffLine {
+                content: "a > b".to_string(),
+                line_type: DiffLineType::Addition,
+                old_line_num: None,
+                new_line_num: Some(1),
+                inline_changes: None,
+            },
+        ];
+        let theme = AppTheme::Dark;
+        let html = diff_html("src/<lib>.rs", &lines, None, &theme, &theme.diff_colors());
+        assert!(html.contains("<title>src/&lt;lib&gt;.rs</title>"));
+        assert!(html.contains("a &lt; b"));
+        assert!(html.contains("a &gt; b"));
+        assert!(html.contains(&css(theme.diff_add_bg())));
+        assert!(html.contains(&css(theme.diff_del_bg())));
+        assert_eq!(html.matches("<tr").count(), 3);
+    }
+
+    #[test]
+    fn test_file_html_uses_syntax_segments() {
+        let red = Color::from_rgb8(0xff, 0, 0);
+        let syntax = vec![SyntaxHighlightLine {
+            segments: vec![SyntaxHighlightSegment {
+                text: "fn".to_string(),
+                color: red,
+            }],
+        }];
+        let html = file_html("a.rs", "fn\nplain", Some(&syntax), &AppTheme::Light);
+        assert!(html.contains("<span style=\"color:#ff0000\">fn</span>"));
+        assert!(html.contains("plain"));
+        assert!(html.contains("<td class=\"num\">2</td>"));
+    }
+}
@@ -0,0 +1,254 @@
+//! Keyboard shortcut table. The key handler matches against these chords and the
+//! help modal is generated from `BINDINGS`, so the two can't drift apart.
+
+use iced::keyboard::Modifiers;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutCategory {
+    Navigation,
+    Tabs,
+    Git,
+    Explorer,
+    Console,
+    Terminal,
+    View,
+}
+
+impl ShortcutCategory {
+    pub const ALL: [ShortcutCategory; 7] = [
+        ShortcutCategory::Navigation,
+        ShortcutCategory::Tabs,
+        ShortcutCategory::Git,
+        ShortcutCategory::Explorer,
+        ShortcutCategory::Console,
+        ShortcutCategory::Terminal,
+        ShortcutCategory::View,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            ShortcutCategory::Navigation => "Navigation",
+            ShortcutCategory::Tabs => "Tabs",
+            ShortcutCategory::Git => "Git",
+            ShortcutCategory::Explorer => "Explorer",
+            ShortcutCategory::Console => "Console",
+            ShortcutCategory::Terminal => "Terminal",
+            ShortcutCategory::View => "View",
+        }
+    }
+}
+
+/// A single key plus required modifiers. Shift must match exactly; the key is
+/// compared case-insensitively since Shift changes the reported character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    pub key: &'static str,
+    pub command: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub control: bool,
+}
+
+impl KeyChord {
+    const fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            command: false,
+            shift: false,
+            alt: false,
+            control: false,
+        }
+    }
+
+    const fn cmd(key: &'static str) -> Self {
+        Self {
+            command: true,
+            ..Self::new(key)
+        }
+    }
+
+    const fn cmd_shift(key: &'static str) -> Self {
+        Self {
+            command: true,
+            shift: true,
+            ..Self::new(key)
+        }
+    }
+
+    const fn alt(key: &'static str) -> Self {
+        Self {
+            alt: true,
+            ..Self::new(key)
+        }
+    }
+
+    const fn alt_shift(key: &'static str) -> Self {
+        Self {
+            alt: true,
+            shift: true,
+            ..Self::new(key)
+        }
+    }
+
+    const fn ctrl(key: &'static str) -> Self {
+        Self {
+            control: true,
+            ..Self::new(key)
+        }
+    }
+
+    pub fn matches(&self, key: &str, modifiers: Modifiers) -> bool {
+        modifiers.command() == self.command
+            && modifiers.shift() == self.shift
+            && (!self.alt || modifiers.alt())
+            && (!self.control || modifiers.control())
+            && key.eq_ignore_ascii_case(self.key)
+    }
+
+    /// Display form used in the help modal, e.g. "Cmd + Shift + F".
+    pub fn label(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        if self.control {
+            parts.push("Ctrl".to_string());
+        }
+        if self.alt {
+            parts.push("Option".to_string());
+        }
+        if self.command {
+            parts.push("Cmd".to_string());
+        }
+        if self.shift {
+            parts.push("Shift".to_string());
+        }
+        parts.push(self.key.to_ascii_uppercase());
+        parts.join(" + ")
+    }
+}
+
+pub const TOGGLE_HELP: KeyChord = KeyChord::alt("/");
+pub const TOGGLE_SIDEBAR: KeyChord = KeyChord::cmd("b");
+pub const NEXT_SIDEBAR_MODE: KeyChord = KeyChord::cmd_shift("e");
+pub const REVIEW_MODE: KeyChord = KeyChord::cmd_shift("m");
+pub const CLOSE_WORKSPACE: KeyChord = KeyChord::cmd_shift("w");
+pub const WORKSPACE_GREP: KeyChord = KeyChord::cmd_shift("f");
+pub const ATTENTION_JUMP: KeyChord = KeyChord::ctrl("`");
+pub const OPEN_FOLDER_TAB: KeyChord = KeyChord::alt_shift("t");
+pub const CONSOLE_TOGGLE: KeyChord = KeyChord::cmd("j");
+pub const CONSOLE_RESTART: KeyChord = KeyChord::cmd_shift("r");
+pub const CONSOLE_MAXIMIZE: KeyChord = KeyChord::cmd_shift("j");
+pub const CONSOLE_NEXT_ERROR: KeyChord = KeyChord::cmd("]");
+pub const CONSOLE_PREV_ERROR: KeyChord = KeyChord::cmd("[");
+pub const CLEAR_TERMINAL: KeyChord = KeyChord::cmd("k");
+pub const FIND: KeyChord = KeyChord::cmd("f");
+pub const FIND_NEXT: KeyChord = KeyChord::cmd("g");
+pub const FIND_PREV: KeyChord = KeyChord::cmd_shift("g");
+pub const TERMINAL_LINKS: KeyChord = KeyChord::cmd_shift("o");
+pub const COPY: KeyChord = KeyChord::cmd("c");
+pub const CLIPBOARD_HISTORY: KeyChord = KeyChord::cmd_shift("v");
+pub const TERMINAL_FONT_UP: KeyChord = KeyChord::cmd("=");
+pub const TERMINAL_FONT_DOWN: KeyChord = KeyChord::cmd("-");
+pub const UI_FONT_UP: KeyChord = KeyChord::cmd_shift("=");
+pub const UI_FONT_DOWN: KeyChord = KeyChord::cmd_shift("-");
+pub const PERF_OVERLAY: KeyChord = KeyChord::cmd_shift("p");
+/// Bound through the native menu accelerator rather than the key handler
+pub const TOGGLE_THEME: KeyChord = KeyChord::cmd_shift("t");
+
+/// How a binding is triggered: a single chord, or a free-form description for
+/// ranges, mouse gestures and context-dependent plain keys.
+#[derive(Debug, Clone, Copy)]
+pub enum Keys {
+    Chord(KeyChord),
+    Text(&'static str),
+}
+
+impl Keys {
+    pub fn label(&self) -> String {
+        match self {
+            Keys::Chord(chord) => chord.label(),
+            Keys::Text(text) => text.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Binding {
+    pub category: ShortcutCategory,
+    pub keys: Keys,
+    pub description: &'static str,
+}
+
+const fn chord(category: ShortcutCategory, keys: KeyChord, description: &'static str) -> Binding {
+    Binding {
+        category,
+        keys: Keys::Chord(keys),
+        description,
+    }
+}
+
+const fn text(category: ShortcutCategory, keys: &'static str, description: &'static str) -> Binding {
+    Binding {
+        category,
+        keys: Keys::Text(keys),
+        description,
+    }
+}
+
+use ShortcutCategory::*;
+
+pub const BINDINGS: &[Binding] = &[
+    text(Navigation, "Ctrl + 1-9", "Switch workspace"),
+    text(Navigation, "Cmd + 1-9", "Switch tab"),
+    chord(Navigation, ATTENTION_JUMP, "Jump to attention tab"),
+    chord(Navigation, CLOSE_WORKSPACE, "Close workspace"),
+    chord(Navigation, TOGGLE_SIDEBAR, "Toggle sidebar"),
+    chord(Navigation, NEXT_SIDEBAR_MODE, "Next sidebar mode (Git, Files, Claude, Agent)"),
+    chord(Navigation, REVIEW_MODE, "Review mode (hide the terminals)"),
+    chord(Navigation, WORKSPACE_GREP, "Search in workspace files"),
+    chord(Navigation, TOGGLE_HELP, "Show this help"),
+    text(Tabs, "+ button", "New Claude tab"),
+    text(Tabs, "Option + Click +", "Tab picker (Claude/Codex/Terminal)"),
+    text(Tabs, "Option + Shift + 1-9", "Launch agent preset"),
+    chord(Tabs, OPEN_FOLDER_TAB, "New terminal (folder)"),
+    text(Git, "j / k", "Next/previous file (in diff)"),
+    text(Git, "g / G", "First/last file (in diff)"),
+    text(Git, "c", "Toggle skipping empty diffs with j/k (in diff)"),
+    text(Git, "e", "Open file in $EDITOR (in diff)"),
+    text(Git, "Esc", "Close diff, file view or filter"),
+    text(Git, "Right-click file", "Copy path or relative path"),
+    text(Explorer, "j / k, \u{2193} / \u{2191}", "Next/previous entry"),
+    text(Explorer, "Enter", "Open file or directory"),
+    text(Explorer, "Backspace", "Parent directory"),
+    text(Explorer, "Esc", "Clear the explorer selection"),
+    chord(Console, CONSOLE_TOGGLE, "Toggle bottom panel"),
+    chord(Console, CONSOLE_MAXIMIZE, "Maximize/restore bottom panel"),
+    chord(Console, CONSOLE_RESTART, "Restart console"),
+    chord(Console, CONSOLE_NEXT_ERROR, "Next error line"),
+    chord(Console, CONSOLE_PREV_ERROR, "Previous error line"),
+    chord(Terminal, CLEAR_TERMINAL, "Clear terminal"),
+    chord(Terminal, FIND, "Find in terminal"),
+    chord(Terminal, FIND_NEXT, "Next match"),
+    chord(Terminal, FIND_PREV, "Previous match"),
+    chord(Terminal, TERMINAL_LINKS, "Open a link from the terminal"),
+    chord(Terminal, CLIPBOARD_HISTORY, "Clipboard history (when enabled)"),
+    chord(View, TERMINAL_FONT_UP, "Increase terminal font"),
+    chord(View, TERMINAL_FONT_DOWN, "Decrease terminal font"),
+    chord(View, UI_FONT_UP, "Increase UI font"),
+    chord(View, UI_FONT_DOWN, "Decrease UI font"),
+    chord(View, TOGGLE_THEME, "Toggle light/dark"),
+    chord(View, PERF_OVERLAY, "Toggle performance overlay"),
+];
+
+/// Bindings grouped by category, in display order; empty categories are skipped.
+pub fn grouped() -> Vec<(ShortcutCategory, Vec<&'static Binding>)> {
+    ShortcutCategory::ALL
+        .iter()
+        .map(|category| {
+            let bindings = BINDINGS
+                .iter()
+                .filter(|binding| binding.category == *category)
+                .collect::<Vec<_>>();
+            (*category, bindings)
+        })
+        .filter(|(_, bindings)| !bindings.is_empty())
+        .collect()
+}
@@ -11,6 +11,13 @@ pub struct TerminalSnapshot {
     pub content: String,
 }
 
+/// Snapshot of a workspace's console output
+#[derive(Clone)]
+pub struct ConsoleSnapshot {
+    pub workspace: String,
+    pub content: String,
+}
+
 /// Snapshot of a file being viewed
 #[derive(Clone)]
 pub struct FileSnapshot {
@@ -23,6 +30,10 @@ pub struct FileSnapshot {
 pub struct ServerState {
     pub terminals: Arc<RwLock<HashMap<usize, TerminalSnapshot>>>,
     pub files: Arc<RwLock<HashMap<usize, FileSnapshot>>>,
+    /// Bottom panel terminals, keyed by bottom terminal id
+    pub bottom_terminals: Arc<RwLock<HashMap<usize, TerminalSnapshot>>>,
+    /// Console output, keyed by workspace index
+    pub consoles: Arc<RwLock<HashMap<usize, ConsoleSnapshot>>>,
     pub shutdown: Arc<tokio::sync::Notify>,
     pub bound_port: Arc<std::sync::Mutex<Option<u16>>>,
 }
@@ -32,6 +43,8 @@ impl ServerState {
         Self {
             terminals: Arc::new(RwLock::new(HashMap::new())),
             files: Arc::new(RwLock::new(HashMap::new())),
+            bottom_terminals: Arc::new(RwLock::new(HashMap::new())),
+            consoles: Arc::new(RwLock::new(HashMap::new())),
             shutdown: Arc::new(tokio::sync::Notify::new()),
             bound_port: Arc::new(std::sync::Mutex::new(None)),
         }
@@ -91,7 +104,17 @@ pub async fn start_server(state: ServerState) {
         .and(state_filter.clone())
         .and_then(handle_file);
 
-    let routes = index.or(tab).or(file);
+    // Route: GET /bottom-terminal/{id} - Show a bottom panel terminal
+    let bottom_terminal = warp::path!("bottom-terminal" / usize)
+        .and(state_filter.clone())
+        .and_then(handle_bottom_terminal);
+
+    // Route: GET /console/{workspace} - Show a workspace's console output
+    let console = warp::path!("console" / usize)
+        .and(state_filter.clone())
+        .and_then(handle_console);
+
+    let routes = index.or(tab).or(file).or(bottom_terminal).or(console);
 
     let Some(port) = find_available_port() else {
         eprintln!("Log server disabled: unable to bind any localhost port");
@@ -122,6 +145,8 @@ pub async fn start_server(state: ServerState) {
 /// Handler for index page - lists all tabs
 async fn handle_index(state: ServerState) -> Result<impl warp::Reply, warp::Rejection> {
     let snapshots = state.terminals.read().await;
+    let bottom_terminals = state.bottom_terminals.read().await;
+    let consoles = state.consoles.read().await;
 
     let mut html = String::from(
         r#"<!DOCTYPE html>
@@ -143,6 +168,10 @@ async fn handle_index(state: ServerState) -> Result<impl warp::Reply, warp::Reje
             border-bottom: 2px solid #4ec9b0;
             padding-bottom: 10px;
         }
+        h2 {
+            color: #4ec9b0;
+            margin-top: 30px;
+        }
         .tab-list {
             list-style: none;
             padding: 0;
@@ -181,26 +210,65 @@ async fn handle_index(state: ServerState) -> Result<impl warp::Reply, warp::Reje
     tabs.sort_by_key(|t| t.tab_id);
 
     for snapshot in tabs {
-        let safe_tab_name = html_escape(&snapshot.tab_name);
-        html.push_str(&format!(
-            r#"        <li class="tab-item">
-            <a href="/tab/{}">{}</a>
-            <span class="tab-id">Tab #{}</span>
-        </li>
-"#,
-            snapshot.tab_id, safe_tab_name, snapshot.tab_id
+        html.push_str(&index_item(
+            &format!("/tab/{}", snapshot.tab_id),
+            &snapshot.tab_name,
+            &format!("Tab #{}", snapshot.tab_id),
         ));
     }
 
+    html.push_str("    </ul>\n");
+
+    if !bottom_terminals.is_empty() {
+        html.push_str("    <h2>Bottom terminals</h2>\n    <ul class=\"tab-list\">\n");
+        let mut terminals: Vec<_> = bottom_terminals.values().collect();
+        terminals.sort_by_key(|t| t.tab_id);
+        for snapshot in terminals {
+            html.push_str(&index_item(
+                &format!("/bottom-terminal/{}", snapshot.tab_id),
+                &snapshot.tab_name,
+                &format!("Terminal #{}", snapshot.tab_id),
+            ));
+        }
+        html.push_str("    </ul>\n");
+    }
+
+    if !consoles.is_empty() {
+        html.push_str("    <h2>Consoles</h2>\n    <ul class=\"tab-list\">\n");
+        let mut workspaces: Vec<_> = consoles.iter().collect();
+        workspaces.sort_by_key(|(idx, _)| **idx);
+        for (idx, snapshot) in workspaces {
+            html.push_str(&index_item(
+                &format!("/console/{}", idx),
+                &snapshot.workspace,
+                &format!("Workspace #{}", idx + 1),
+            ));
+        }
+        html.push_str("    </ul>\n");
+    }
+
     html.push_str(
-        r#"    </ul>
-</body>
+        r#"</body>
 </html>"#,
     );
 
     Ok(warp::reply::html(html))
 }
 
+/// One link in the index page's lists
+fn index_item(href: &str, name: &str, id: &str) -> String {
+    format!(
+        r#"        <li class="tab-item">
+            <a href="{}">{}</a>
+            <span class="tab-id">{}</span>
+        </li>
+"#,
+        href,
+        html_escape(name),
+        id
+    )
+}
+
 /// Handler for tab page - shows terminal content
 async fn handle_tab(
     tab_id: usize,
@@ -208,10 +276,57 @@ async fn handle_tab(
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let snapshots = state.terminals.read().await;
 
-    if let Some(snapshot) = snapshots.get(&tab_id) {
-        let safe_tab_name = html_escape(&snapshot.tab_name);
-        let html = format!(
-            r#"<!DOCTYPE html>
+    let html = match snapshots.get(&tab_id) {
+        Some(snapshot) => terminal_page(
+            &snapshot.tab_name,
+            &format!("{} (Tab #{})", snapshot.tab_name, tab_id),
+            &snapshot.content,
+        ),
+        None => not_found_page(&format!("Tab #{}", tab_id)),
+    };
+    Ok(warp::reply::html(html))
+}
+
+/// Handler for bottom terminal page - shows a bottom panel terminal's content
+async fn handle_bottom_terminal(
+    id: usize,
+    state: ServerState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let snapshots = state.bottom_terminals.read().await;
+
+    let html = match snapshots.get(&id) {
+        Some(snapshot) => terminal_page(
+            &snapshot.tab_name,
+            &format!("{} (Bottom terminal #{})", snapshot.tab_name, id),
+            &snapshot.content,
+        ),
+        None => not_found_page(&format!("Bottom terminal #{}", id)),
+    };
+    Ok(warp::reply::html(html))
+}
+
+/// Handler for console page - shows a workspace's run command output
+async fn handle_console(
+    workspace: usize,
+    state: ServerState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let consoles = state.consoles.read().await;
+
+    let html = match consoles.get(&workspace) {
+        Some(snapshot) => terminal_page(
+            &snapshot.workspace,
+            &format!("{} console", snapshot.workspace),
+            &snapshot.content,
+        ),
+        None => not_found_page(&format!("Console #{}", workspace)),
+    };
+    Ok(warp::reply::html(html))
+}
+
+/// Searchable page showing terminal-like output
+fn terminal_page(title: &str, heading: &str, content: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
 <html>
 <head>
     <meta charset="utf-8">
@@ -348,7 +463,7 @@ async fn handle_tab(
 </head>
 <body>
     <div class="header">
-        <h1>{} (Tab #{})</h1>
+        <h1>{}</h1>
         <a href="/">← Back to all tabs</a>
     </div>
     <div class="actions">
@@ -361,20 +476,21 @@ async fn handle_tab(
     <pre id="terminal-content">{}</pre>
 </body>
 </html>"#,
-            safe_tab_name,
-            safe_tab_name,
-            tab_id,
-            html_escape(&snapshot.content)
-        );
+        html_escape(title),
+        html_escape(heading),
+        html_escape(content)
+    )
+}
 
-        Ok(warp::reply::html(html))
-    } else {
-        Ok(warp::reply::html(format!(
-            r#"<!DOCTYPE html>
+/// Page for a tab, terminal or console that no longer exists
+fn not_found_page(what: &str) -> String {
+    let what = html_escape(what);
+    format!(
+        r#"<!DOCTYPE html>
 <html>
 <head>
     <meta charset="utf-8">
-    <title>Tab Not Found</title>
+    <title>{} Not Found</title>
     <style>
         body {{
             font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', sans-serif;
@@ -394,13 +510,12 @@ async fn handle_tab(
     </style>
 </head>
 <body>
-    <h1>Tab #{} Not Found</h1>
+    <h1>{} Not Found</h1>
     <p><a href="/">← Back to all tabs</a></p>
 </body>
 </html>"#,
-            tab_id
-        )))
-    }
+        what, what
+    )
 }
 
 /// Handler for file page - shows file content
@@ -632,4 +747,21 @@ mod tests {
         // Empty string produces no lines from .lines(), so result is empty
         assert_eq!(result, "");
     }
+
+    // === pages ===
+
+    #[test]
+    fn terminal_page_escapes_names_and_output() {
+        let page = terminal_page("<api>", "<api> console", "ok & <done>");
+        assert!(page.contains("<title>&lt;api&gt; - GitTerm Logs</title>"));
+        assert!(page.contains("<h1>&lt;api&gt; console</h1>"));
+        assert!(page.contains(">ok &amp; &lt;done&gt;</pre>"));
+    }
+
+    #[test]
+    fn index_item_links_to_its_page() {
+        let item = index_item("/console/0", "web & api", "Workspace #1");
+        assert!(item.contains(r#"<a href="/console/0">web &amp; api</a>"#));
+        assert!(item.contains("Workspace #1"));
+    }
 }
@@ -1,4 +1,30 @@
-use pulldown_cmark::{html, Options, Parser};
+use crate::config::MarkdownRender;
+use pulldown_cmark::{html, Event, Options, Parser, Tag};
+
+/// Opening tag of the blocks `process_mermaid_blocks` hands to Mermaid
+const MERMAID_OPEN: &str = "<pre class=\"mermaid\">";
+
+/// Reports the heading at the top of the page back to the app, so toggling to the
+/// source can land on the same section. Headings are numbered by `number_headings`.
+const SCROLL_SYNC_SCRIPT: &str = r#"
+<script>
+    (function () {
+        let reported = null;
+        function report() {
+            let current = '';
+            for (const heading of document.querySelectorAll('[data-heading]')) {
+                if (heading.getBoundingClientRect().top > 8) break;
+                current = heading.dataset.heading;
+            }
+            if (current !== reported && window.ipc) {
+                reported = current;
+                window.ipc.postMessage('heading:' + current);
+            }
+        }
+        window.addEventListener('scroll', report, { passive: true });
+        window.addEventListener('load', report);
+    })();
+</script>"#;
 
 /// Theme colors for HTML generation (mirrors AppTheme colors)
 #[allow(dead_code)]
@@ -54,7 +80,11 @@ impl ThemeColors {
 }
 
 /// Render markdown content to a complete HTML document with theme styling and Mermaid support
-pub fn render_markdown_to_html(content: &str, is_dark_theme: bool) -> String {
+pub fn render_markdown_to_html(
+    content: &str,
+    is_dark_theme: bool,
+    options: &MarkdownRender,
+) -> String {
     let theme = if is_dark_theme {
         ThemeColors::dark()
     } else {
@@ -64,20 +94,120 @@ pub fn render_markdown_to_html(content: &str, is_dark_theme: bool) -> String {
     // Process markdown and extract mermaid blocks
     let (processed_content, has_mermaid) = process_mermaid_blocks(content);
 
-    // Parse markdown with GFM extensions
-    let options = Options::ENABLE_TABLES
-        | Options::ENABLE_STRIKETHROUGH
-        | Options::ENABLE_TASKLISTS
-        | Options::ENABLE_FOOTNOTES;
-
-    let parser = Parser::new_ext(&processed_content, options);
+    let parser = Parser::new_ext(&processed_content, markdown_options());
 
     // Convert to HTML
     let mut html_content = String::new();
-    html::push_html(&mut html_content, parser);
+    if options.allow_html {
+        html::push_html(
+            &mut html_content,
+            number_headings(parser, options.scroll_sync),
+        );
+    } else {
+        let events = escape_raw_html(parser);
+        html::push_html(
+            &mut html_content,
+            number_headings(events, options.scroll_sync),
+        );
+    }
+
+    // A missing or unreadable stylesheet just leaves the built-in look
+    let custom_css = options
+        .css_path
+        .as_ref()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .unwrap_or_default();
 
     // Build the complete HTML document
-    build_html_document(&html_content, &theme, has_mermaid, is_dark_theme)
+    build_html_document(
+        &html_content,
+        &theme,
+        has_mermaid,
+        is_dark_theme,
+        &custom_css,
+        options.scroll_sync,
+    )
+}
+
+/// Parse markdown with GFM extensions
+fn markdown_options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES
+}
+
+/// Tag each heading with `data-heading="n"`, counting from 0 in document order, so
+/// the rendered page and `heading_lines` agree on which heading is which.
+fn number_headings<'a>(
+    events: impl Iterator<Item = Event<'a>>,
+    enabled: bool,
+) -> impl Iterator<Item = Event<'a>> {
+    let mut next = 0usize;
+    events.map(move |event| match event {
+        Event::Start(Tag::Heading {
+            level,
+            id,
+            classes,
+            mut attrs,
+        }) if enabled => {
+            attrs.push(("data-heading".into(), Some(next.to_string().into())));
+            next += 1;
+            Event::Start(Tag::Heading {
+                level,
+                id,
+                classes,
+                attrs,
+            })
+        }
+        other => other,
+    })
+}
+
+/// 1-based source line of each heading, indexed the way `number_headings` numbers them
+pub fn heading_lines(content: &str) -> Vec<usize> {
+    Parser::new_ext(content, markdown_options())
+        .into_offset_iter()
+        .filter(|(event, _)| matches!(event, Event::Start(Tag::Heading { .. })))
+        .map(|(_, range)| content[..range.start].matches('\n').count() + 1)
+        .collect()
+}
+
+/// Index of the last heading at or above `line`, or None above the first heading
+pub fn heading_at_line(heading_lines: &[usize], line: usize) -> Option<usize> {
+    heading_lines
+        .iter()
+        .rposition(|&heading_line| heading_line <= line)
+}
+
+/// Scroll the rendered page to a `data-heading` once it has loaded
+pub fn scroll_to_heading(html: &str, heading: usize) -> String {
+    let script = format!(
+        "<script>window.addEventListener('load', () => document\
+         .querySelector('[data-heading=\"{heading}\"]')?.scrollIntoView());</script>\n"
+    );
+    let mut html = html.to_string();
+    let at = html.rfind("</body>").unwrap_or(html.len());
+    html.insert_str(at, &script);
+    html
+}
+
+/// Turn HTML written in the Markdown into text, keeping only the Mermaid blocks'
+/// own tags. Their diagram source stays text too, which Mermaid reads unescaped.
+fn escape_raw_html<'a>(parser: Parser<'a>) -> impl Iterator<Item = Event<'a>> {
+    let mut in_mermaid = false;
+    parser.map(move |event| match event {
+        Event::Html(html) if html.trim_end() == MERMAID_OPEN => {
+            in_mermaid = true;
+            Event::Html(html)
+        }
+        Event::Html(html) if in_mermaid && html.trim_end() == "</pre>" => {
+            in_mermaid = false;
+            Event::Html(html)
+        }
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        other => other,
+    })
 }
 
 /// Process content to convert ```mermaid code blocks to <pre class="mermaid">
@@ -95,7 +225,9 @@ fn process_mermaid_blocks(content: &str) -> (String, bool) {
         } else if in_mermaid_block && line.trim() == "```" {
             in_mermaid_block = false;
             // Output as HTML that won't be escaped by pulldown-cmark
-            result.push_str("\n<pre class=\"mermaid\">\n");
+            result.push('\n');
+            result.push_str(MERMAID_OPEN);
+            result.push('\n');
             result.push_str(&mermaid_content);
             result.push_str("</pre>\n\n");
         } else if in_mermaid_block {
@@ -116,6 +248,8 @@ fn build_html_document(
     theme: &ThemeColors,
     has_mermaid: bool,
     is_dark_theme: bool,
+    custom_css: &str,
+    scroll_sync: bool,
 ) -> String {
     let mermaid_theme = if is_dark_theme { "dark" } else { "default" };
 
@@ -330,10 +464,13 @@ fn build_html_document(
             border-top: 1px solid {border};
         }}
     </style>
+    <style>
+{custom_css}
+    </style>
     {mermaid_script}
 </head>
 <body>
-{content}
+{content}{sync_script}
 </body>
 </html>"#,
         text_primary = theme.text_primary,
@@ -343,8 +480,10 @@ fn build_html_document(
         code_bg = theme.code_bg,
         text_secondary = theme.text_secondary,
         bg_surface = theme.bg_surface,
+        custom_css = custom_css,
         mermaid_script = mermaid_script,
         content = content,
+        sync_script = if scroll_sync { SCROLL_SYNC_SCRIPT } else { "" },
     )
 }
 
@@ -355,7 +494,7 @@ mod tests {
     #[test]
     fn test_basic_markdown() {
         let content = "# Hello\n\nThis is a test.";
-        let html = render_markdown_to_html(content, true);
+        let html = render_markdown_to_html(content, true, &MarkdownRender::default());
         assert!(html.contains("<h1>Hello</h1>"));
         assert!(html.contains("<p>This is a test.</p>"));
     }
@@ -385,18 +524,83 @@ mod tests {
 
     #[test]
     fn test_render_dark_theme() {
-        let html = render_markdown_to_html("# Hello", true);
+        let html = render_markdown_to_html("# Hello", true, &MarkdownRender::default());
         // Dark theme uses Catppuccin Mocha bg
         assert!(html.contains("#1e1e2e"));
     }
 
     #[test]
     fn test_render_light_theme() {
-        let html = render_markdown_to_html("# Hello", false);
+        let html = render_markdown_to_html("# Hello", false, &MarkdownRender::default());
         // Light theme uses Catppuccin Latte bg
         assert!(html.contains("#eff1f5"));
     }
 
+    #[test]
+    fn test_raw_html_can_be_escaped() {
+        let content = concat!(
+            "<details><summary>More</summary>\n\nHidden\n\n</details>\n\n",
+            "A <b>bold</b> word\n\n```mermaid\ngraph TD\nA --> B\n```\n",
+        );
+        let allowed = render_markdown_to_html(content, true, &MarkdownRender::default());
+        assert!(allowed.contains("<details><summary>More</summary>"));
+        assert!(allowed.contains("<b>bold</b>"));
+
+        let options = MarkdownRender {
+            allow_html: false,
+            ..MarkdownRender::default()
+        };
+        let escaped = render_markdown_to_html(content, true, &options);
+        assert!(escaped.contains("&lt;details&gt;&lt;summary&gt;More&lt;/summary&gt;"));
+        assert!(escaped.contains("A &lt;b&gt;bold&lt;/b&gt; word"));
+        // Mermaid diagrams still render
+        assert!(escaped.contains("<pre class=\"mermaid\">\ngraph TD\nA --&gt; B\n</pre>"));
+    }
+
+    #[test]
+    fn test_custom_css_follows_builtin_styles() {
+        let dir = tempfile::tempdir().unwrap();
+        let css_path = dir.path().join("docs.css");
+        std::fs::write(&css_path, "h1 { color: rebeccapurple; }").unwrap();
+        let options = MarkdownRender {
+            css_path: Some(css_path),
+            ..MarkdownRender::default()
+        };
+        let html = render_markdown_to_html("# Hello", true, &options);
+        let custom = html.find("rebeccapurple").unwrap();
+        assert!(custom > html.find("box-sizing").unwrap());
+        assert!(custom < html.find("<body>").unwrap());
+    }
+
+    #[test]
+    fn test_scroll_sync_numbers_headings_by_source_line() {
+        let content = concat!(
+            "Intro\n\n# Title\n\n```mermaid\ngraph TD\n```\n\n",
+            "Setext\n------\n\n```\n# not a heading\n```\n\n### Last\n",
+        );
+        assert_eq!(heading_lines(content), vec![3, 9, 16]);
+        assert_eq!(heading_at_line(&[3, 9, 16], 2), None);
+        assert_eq!(heading_at_line(&[3, 9, 16], 12), Some(1));
+
+        let plain = render_markdown_to_html(content, true, &MarkdownRender::default());
+        assert!(plain.contains("<h1>Title</h1>"));
+        assert!(!plain.contains("window.ipc"));
+
+        let options = MarkdownRender {
+            scroll_sync: true,
+            ..MarkdownRender::default()
+        };
+        let html = render_markdown_to_html(content, true, &options);
+        assert!(html.contains("<h1 data-heading=\"0\">Title</h1>"));
+        assert!(html.contains("<h2 data-heading=\"1\">Setext</h2>"));
+        assert!(html.contains("<h3 data-heading=\"2\">Last</h3>"));
+        assert!(html.contains("window.ipc.postMessage"));
+
+        let scrolled = scroll_to_heading(&html, 2);
+        assert!(scrolled.ends_with("scrollIntoView());</script>\n</body>\n</html>"));
+        assert!(scrolled.contains("[data-heading=\"2\"]"));
+    }
+
     #[test]
     fn test_theme_colors_dark() {
         let dark = ThemeColors::dark();
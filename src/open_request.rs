@@ -0,0 +1,306 @@
+//! "Open in GitTerm" requests: a folder, or a file and line, passed on the command
+//! line as a plain path or as `gitterm://open?path=<percent-encoded path>&line=<n>`.
+//!
+//! Each instance listens on a loopback port recorded, with a random token, in
+//! `~/.config/gitterm/open.port` (readable by the owner only). A new launch with a path
+//! first hands the request to that instance, presenting the token, and exits; if nothing
+//! answers it starts normally. Connections without the token are refused, so other local
+//! users can't open folders (and spawn shells) in someone else's GitTerm.
+//!
+//! macOS Launch Services isn't wired up: the `gitterm://` scheme isn't registered for the
+//! bundle, so `open gitterm://...` doesn't reach GitTerm. Tools pass the URL as an argument.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const URL_PREFIX: &str = "gitterm://open?";
+
+const HANDOFF_TIMEOUT: Duration = Duration::from_millis(500);
+const MAX_REQUEST_BYTES: u64 = 8 * 1024;
+const TOKEN_BYTES: usize = 16;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenRequest {
+    pub path: PathBuf,
+    /// 1-based line to scroll to when `path` is a file
+    pub line: Option<usize>,
+}
+
+impl OpenRequest {
+    /// Parse a command-line argument: a `gitterm://` URL or a plain path.
+    pub fn from_arg(arg: &str) -> Option<Self> {
+        if arg.starts_with("gitterm://") {
+            return Self::from_url(arg);
+        }
+        Some(Self {
+            path: PathBuf::from(arg),
+            line: None,
+        })
+    }
+
+    pub fn from_url(url: &str) -> Option<Self> {
+        let query = url.strip_prefix(URL_PREFIX)?;
+        let mut path = None;
+        let mut line = None;
+        for pair in query.split('&') {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            match key {
+                "path" => path = Some(PathBuf::from(percent_decode(value)?)),
+                "line" => line = value.parse::<usize>().ok().filter(|l| *l > 0),
+                _ => {}
+            }
+        }
+        Some(Self { path: path?, line })
+    }
+
+    pub fn to_url(&self) -> String {
+        let mut url = format!(
+            "{}path={}",
+            URL_PREFIX,
+            percent_encode(&self.path.to_string_lossy())
+        );
+        if let Some(line) = self.line {
+            url.push_str(&format!("&line={}", line));
+        }
+        url
+    }
+
+    /// Make the path absolute and check it exists.
+    pub fn resolved(self, cwd: &Path) -> Result<Self, String> {
+        let path = if self.path.is_absolute() {
+            self.path
+        } else {
+            cwd.join(self.path)
+        };
+        match path.canonicalize() {
+            Ok(path) if path.is_dir() || path.is_file() => Ok(Self {
+                path,
+                line: self.line,
+            }),
+            _ => Err(format!("cannot open {}: no such file or directory", path.display())),
+        }
+    }
+
+    /// Folder to open as a workspace: the path itself, or a file's parent.
+    pub fn folder(&self) -> PathBuf {
+        if self.path.is_file() {
+            self.path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| self.path.clone())
+        } else {
+            self.path.clone()
+        }
+    }
+
+    pub fn file(&self) -> Option<&Path> {
+        self.path.is_file().then_some(self.path.as_path())
+    }
+}
+
+fn port_file() -> PathBuf {
+    let instance_dir = crate::config::instance_config_dir();
+    instance_dir
+        .parent()
+        .unwrap_or(&instance_dir)
+        .join("open.port")
+}
+
+/// Port and token of the running instance, as written by `start_listener`.
+fn read_port_file() -> Option<(u16, String)> {
+    let contents = std::fs::read_to_string(port_file()).ok()?;
+    let (port, token) = contents.trim().split_once(' ')?;
+    Some((port.parse().ok()?, token.to_string()))
+}
+
+/// Write `contents` to a fresh file only the current user can read.
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    // create_new below won't follow a planted symlink or keep old permissions
+    let _ = std::fs::remove_file(path);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(contents.as_bytes())
+}
+
+/// Random hex token from the OS random source.
+fn new_token() -> Option<String> {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    std::fs::File::open("/dev/urandom")
+        .ok()?
+        .read_exact(&mut bytes)
+        .ok()?;
+    Some(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Compare without bailing out at the first differing byte.
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Validate one handoff line, `<token> <gitterm:// URL>`.
+fn parse_handoff(line: &str, token: &str) -> Result<OpenRequest, String> {
+    let (given, url) = line.trim().split_once(' ').unwrap_or(("", ""));
+    if !tokens_match(given, token) {
+        return Err("open request rejected: missing or wrong token".to_string());
+    }
+    let request = OpenRequest::from_url(url).ok_or_else(|| "malformed open request".to_string())?;
+    if !request.path.is_absolute() {
+        return Err("open request path must be absolute".to_string());
+    }
+    request.resolved(Path::new("/"))
+}
+
+/// Hand the request to an already running instance. Returns false when none answered.
+pub fn forward_to_running_instance(request: &OpenRequest) -> bool {
+    let Some((port, token)) = read_port_file() else {
+        return false;
+    };
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, HANDOFF_TIMEOUT) else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(HANDOFF_TIMEOUT));
+    if writeln!(stream, "{} {}", token, request.to_url()).is_err() {
+        return false;
+    }
+    // Only trust the handoff if the listener acknowledges it; the port may be stale.
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply).is_ok() && reply.trim() == "ok"
+}
+
+/// Start accepting forwarded requests. Validated requests arrive on the returned channel.
+/// Without a random source for the token no listener is started.
+pub fn start_listener() -> Option<tokio::sync::mpsc::UnboundedReceiver<OpenRequest>> {
+    let token = new_token()?;
+    let listener = TcpListener::bind(("127.0.0.1", 0)).ok()?;
+    let port = listener.local_addr().ok()?.port();
+    let path = port_file();
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    write_private(&path, &format!("{} {}", port, token)).ok()?;
+
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let _ = stream.set_read_timeout(Some(HANDOFF_TIMEOUT));
+            let mut line = String::new();
+            if BufReader::new((&stream).take(MAX_REQUEST_BYTES))
+                .read_line(&mut line)
+                .is_err()
+            {
+                continue;
+            }
+            match parse_handoff(&line, &token) {
+                Ok(request) => {
+                    let _ = writeln!(stream, "ok");
+                    if tx.send(request).is_err() {
+                        break;
+                    }
+                }
+                Err(err) => {
+                    eprintln!("gitterm: {}", err);
+                    let _ = writeln!(stream, "error");
+                }
+            }
+        }
+    });
+    Some(rx)
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+                out.push(u8::from_str_radix(hex, 16).ok()?);
+                i += 3;
+            }
+            b'+' => {
+                out.push(b' ');
+                i += 1;
+            }
+            byte => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_round_trip() {
+        let request = OpenRequest {
+            path: PathBuf::from("/tmp/my repo/src/a+b.rs"),
+            line: Some(42),
+        };
+        let url = request.to_url();
+        assert_eq!(url, "gitterm://open?path=/tmp/my%20repo/src/a%2Bb.rs&line=42");
+        assert_eq!(OpenRequest::from_url(&url), Some(request));
+    }
+
+    #[test]
+    fn test_from_arg_and_bad_urls() {
+        assert_eq!(
+            OpenRequest::from_arg("repo"),
+            Some(OpenRequest {
+                path: PathBuf::from("repo"),
+                line: None
+            })
+        );
+        assert_eq!(
+            OpenRequest::from_arg("gitterm://open?path=%2Ftmp&line=0"),
+            Some(OpenRequest {
+                path: PathBuf::from("/tmp"),
+                line: None
+            })
+        );
+        assert_eq!(OpenRequest::from_arg("gitterm://open?line=3"), None);
+        assert_eq!(OpenRequest::from_arg("gitterm://open?path=%zz"), None);
+        assert_eq!(OpenRequest::from_arg("gitterm://delete?path=/tmp"), None);
+    }
+
+    #[test]
+    fn test_handoff_requires_the_token() {
+        let token = "0123abcd";
+        let url = "gitterm://open?path=/&line=3";
+        let request = parse_handoff(&format!("{} {}\n", token, url), token).unwrap();
+        assert_eq!(request.path, Path::new("/").canonicalize().unwrap());
+        assert_eq!(request.line, Some(3));
+
+        assert!(parse_handoff(url, token).is_err());
+        assert!(parse_handoff(&format!("0123abce {}", url), token).is_err());
+        assert!(parse_handoff(&format!("0123abc {}", url), token).is_err());
+        assert!(parse_handoff(&format!("{} gitterm://open?path=relative", token), token).is_err());
+    }
+}
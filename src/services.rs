@@ -1,15 +1,24 @@
 #[cfg(feature = "excalidraw")]
 use crate::excalidraw;
+use crate::config::{GitAuthor, OpenMode};
 use crate::markdown;
 use crate::{
-    add_word_diffs_to_lines, build_syntax_highlight_lines, format_bytes, read_text_preview,
-    DiffLine, DiffLineType, DiffSnapshot, FileEntry, FileLoadSnapshot,
-    FileSyntaxSnapshot, FileTreeEntry, FileTreeSnapshot, FileVersionSignature, GitStatusSnapshot,
-    TabState, LARGE_TEXT_PREVIEW_BYTES, LARGE_TEXT_PREVIEW_LINES, MAX_FULL_TEXT_LOAD_BYTES,
-    MAX_INLINE_WEBVIEW_BYTES,
+    add_word_diffs_to_lines, build_syntax_highlight_lines, file_load_limits, format_bytes,
+    gitignore_pattern, markdown_render, open_mode, read_text_preview, read_text_tail,
+    text_diff_lines, MAX_FILE_VIEW_RENDER_LINES,
+    CompareSnapshot, DiffLine, DiffLineType, DiffSnapshot, FileEntry, FileLoadSnapshot,
+    FileSyntaxSnapshot, GitOpKind, GitOpSnapshot, LineBlame, LineBlameSnapshot, FileTreeEntry, FileTreeSnapshot, FileVersionSignature, GitStatusSnapshot,
+    GrepFileMatches, GrepLineMatch, GrepMessage, RefKind, RepoOperation, SearchOptions, TabState,
+    BaseDiffSnapshot, BranchListSnapshot, FileLastCommit, FileLastCommitSnapshot, CommitLogEntry, CommitLogSnapshot, EmptyDiffsSnapshot,
+    MAX_GREP_MATCHES, MAX_GREP_PREVIEW_CHARS, ProcessUsage, sum_process_group_usage,
+    explorer_order, file_version_signature, sort_file_tree, Thumbnail, THUMBNAIL_MAX_FILE_BYTES,
+    THUMBNAIL_SIZE, RemoteProgress,
 };
-use git2::{DiffOptions, Repository, Status, StatusOptions};
+use git2::{BlameOptions, DiffOptions, Repository, Status, StatusOptions};
+use std::collections::HashSet;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::{Instant, UNIX_EPOCH};
 
 macro_rules! perf_log {
@@ -21,6 +30,9 @@ macro_rules! perf_log {
 }
 
 const MAX_UNTRACKED_DIFF_PREVIEW_LINES: usize = 3000;
+/// How far back `file_last_commit` walks before giving up.
+const LAST_COMMIT_WALK_LIMIT: usize = 20_000;
+const AMEND_MERGE_ERROR: &str = "HEAD is a merge commit; amend it from the terminal";
 
 pub(crate) fn collect_git_status(tab_id: usize, repo_path: PathBuf) -> GitStatusSnapshot {
     let started = Instant::now();
@@ -33,12 +45,27 @@ pub(crate) fn collect_git_status(tab_id: usize, repo_path: PathBuf) -> GitStatus
             .unwrap_or_else(|| "repo".to_string()),
         repo_path: repo_path.clone(),
         branch_name: "main".to_string(),
+        head_oid: None,
         is_git_repo: false,
         staged: Vec::new(),
         unstaged: Vec::new(),
         untracked: Vec::new(),
+        untracked_dirs: Vec::new(),
+        upstream: None,
+        ahead_behind: None,
+        conflicted: Vec::new(),
+        operation: RepoOperation::None,
+        dir_missing: false,
+        tracked_files: None,
+        error: None,
     };
 
+    // Deleted out from under the tab; running git there would fail like a missing binary
+    if !repo_path.is_dir() {
+        snapshot.dir_missing = true;
+        return snapshot;
+    }
+
     // Use native git CLI — faster than git2 because it uses fsmonitor,
     // split index, untracked cache, and other optimizations.
     //
@@ -52,18 +79,39 @@ pub(crate) fn collect_git_status(tab_id: usize, repo_path: PathBuf) -> GitStatus
             "--porcelain=v2",
             "--branch",
         ])
+        // English messages, so "not a git repository" can be recognised below
+        .env("LC_ALL", "C")
         .current_dir(&repo_path)
         .output();
 
     // If git binary isn't found, fall back to git2 library.
-    // If git ran but returned non-zero, the directory isn't a git repo — don't bother with git2.
+    // If git ran but returned non-zero, the directory usually isn't a git repo — don't
+    // bother with git2. Any other failure (locked or corrupt index, unsafe ownership)
+    // is reported so it doesn't pass for a clean tree.
     let output = match cli_result {
         Ok(o) if o.status.success() => o,
-        Ok(_) => return snapshot, // git ran, not a git repo
+        Ok(o) => {
+            let stderr = String::from_utf8_lossy(&o.stderr);
+            if !stderr.contains("not a git repository") {
+                snapshot.is_git_repo = true;
+                snapshot.error = Some(
+                    stderr
+                        .lines()
+                        .map(str::trim)
+                        .find(|line| !line.is_empty())
+                        .unwrap_or("git status failed")
+                        .to_string(),
+                );
+            }
+            return snapshot;
+        }
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
             return collect_git_status_git2(snapshot, &repo_path);
         }
-        Err(_) => return snapshot,
+        Err(e) => {
+            snapshot.error = Some(format!("Couldn't run git: {}", e));
+            return snapshot;
+        }
     };
     
     snapshot.is_git_repo = true;
@@ -76,6 +124,22 @@ pub(crate) fn collect_git_status(tab_id: usize, repo_path: PathBuf) -> GitStatus
             if !branch.is_empty() && branch != "(detached)" {
                 snapshot.branch_name = branch.to_string();
             }
+        } else if let Some(oid) = line.strip_prefix("# branch.oid ") {
+            // "(initial)" on an unborn branch
+            let oid = oid.trim();
+            if oid != "(initial)" {
+                snapshot.head_oid = Some(oid.to_string());
+            }
+        } else if let Some(upstream) = line.strip_prefix("# branch.upstream ") {
+            snapshot.upstream = Some(upstream.trim().to_string());
+        } else if let Some(ab) = line.strip_prefix("# branch.ab ") {
+            // "# branch.ab +<ahead> -<behind>"
+            let mut parts = ab.split_whitespace();
+            let ahead = parts.next().and_then(|a| a.trim_start_matches('+').parse().ok());
+            let behind = parts.next().and_then(|b| b.trim_start_matches('-').parse().ok());
+            if let (Some(ahead), Some(behind)) = (ahead, behind) {
+                snapshot.ahead_behind = Some((ahead, behind));
+            }
         } else if line.starts_with("1 ") || line.starts_with("2 ") {
             // Changed entries: "1 XY sub mH mI mW hH hI path"
             // or rename:       "2 XY sub mH mI mW hH hI X### path\torigPath"
@@ -142,6 +206,13 @@ pub(crate) fn collect_git_status(tab_id: usize, repo_path: PathBuf) -> GitStatus
                 }),
                 _ => {}
             }
+        } else if line.starts_with("u ") {
+            // Unmerged: "u XY sub m1 m2 m3 mW h1 h2 h3 path" — path follows the 10th space
+            if let Some(path) = line.splitn(11, ' ').nth(10).filter(|p| !p.is_empty()) {
+                snapshot.conflicted.push(FileEntry {
+                    path: path.to_string(), status: "U".to_string(), is_staged: false,
+                });
+            }
         } else if line.starts_with("? ") {
             // Untracked: "? path"
             let path = line[2..].to_string();
@@ -149,9 +220,8 @@ pub(crate) fn collect_git_status(tab_id: usize, repo_path: PathBuf) -> GitStatus
                 path, status: "?".to_string(), is_staged: false,
             });
         }
-        // Skip "u " (unmerged) and other header lines for now
     }
-    
+
     // Self-heal repo path: check if .git exists at repo_path, otherwise discover root
     if !repo_path.join(".git").exists() {
         if let Ok(toplevel_output) = std::process::Command::new("git")
@@ -173,6 +243,46 @@ pub(crate) fn collect_git_status(tab_id: usize, repo_path: PathBuf) -> GitStatus
         }
     }
 
+    // MERGE_HEAD, rebase-merge/ etc. live in the git dir; git2 resolves it from the
+    // root found above (worktrees too) without walking up from the tab's folder
+    if let Ok(repo) = Repository::open(&snapshot.repo_path) {
+        snapshot.operation = RepoOperation::from_state(repo.state());
+        snapshot.tracked_files = index_entry_count(&repo.path().join("index"));
+    }
+
+    // In the default untracked mode git reports a wholly new directory as a single
+    // "newdir/" entry. Expand those into their files so they can be diffed, and
+    // remember the directories so the sidebar can group them.
+    let new_dirs: Vec<String> = snapshot
+        .untracked
+        .iter()
+        .filter(|f| f.path.ends_with('/'))
+        .map(|f| f.path.clone())
+        .collect();
+    if !new_dirs.is_empty() {
+        if let Ok(ls_output) = std::process::Command::new("git")
+            .args(["--no-optional-locks", "ls-files", "--others", "--exclude-standard", "-z", "--"])
+            .args(&new_dirs)
+            .current_dir(&snapshot.repo_path)
+            .output()
+        {
+            if ls_output.status.success() {
+                snapshot.untracked.retain(|f| !f.path.ends_with('/'));
+                for path in String::from_utf8_lossy(&ls_output.stdout).split('\0') {
+                    if !path.is_empty() {
+                        snapshot.untracked.push(FileEntry {
+                            path: path.to_string(),
+                            status: "?".to_string(),
+                            is_staged: false,
+                        });
+                    }
+                }
+                snapshot.untracked.sort_by(|a, b| a.path.cmp(&b.path));
+                snapshot.untracked_dirs = new_dirs;
+            }
+        }
+    }
+    
     let elapsed = started.elapsed();
     perf_log!(
         "git_status tab={} repo={} git={} changed={} took={}ms",
@@ -193,6 +303,61 @@ pub(crate) fn collect_git_status(tab_id: usize, repo_path: PathBuf) -> GitStatus
     snapshot
 }
 
+pub(crate) fn collect_thumbnails(
+    wanted: Vec<(PathBuf, Option<FileVersionSignature>)>,
+) -> Vec<Thumbnail> {
+    let started = Instant::now();
+    let thumbnails: Vec<Thumbnail> = wanted
+        .into_iter()
+        .filter_map(|(path, known)| {
+            let signature = file_version_signature(&path);
+            if signature.is_some() && signature == known {
+                return None;
+            }
+            let handle = signature
+                .filter(|s| s.file_len <= THUMBNAIL_MAX_FILE_BYTES)
+                .and_then(|_| decode_thumbnail(&path));
+            Some(Thumbnail {
+                path,
+                signature,
+                handle,
+                used_at: Instant::now(),
+            })
+        })
+        .collect();
+    perf_log!(
+        "thumbnails decoded={} took={}ms",
+        thumbnails.len(),
+        started.elapsed().as_millis()
+    );
+    thumbnails
+}
+
+fn decode_thumbnail(path: &std::path::Path) -> Option<iced::widget::image::Handle> {
+    let decoded = ::image::ImageReader::open(path)
+        .ok()?
+        .with_guessed_format()
+        .ok()?
+        .decode()
+        .ok()?;
+    let thumbnail = decoded.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).into_rgba8();
+    let (width, height) = thumbnail.dimensions();
+    Some(iced::widget::image::Handle::from_rgba(width, height, thumbnail.into_raw()))
+}
+
+/// Number of tracked files, read from the index header ("DIRC", version, entry
+/// count) so huge repos cost twelve bytes rather than a full index load.
+pub(crate) fn index_entry_count(index_path: &std::path::Path) -> Option<usize> {
+    use std::io::Read;
+    let mut header = [0u8; 12];
+    std::fs::File::open(index_path).ok()?.read_exact(&mut header).ok()?;
+    if &header[..4] != b"DIRC" {
+        return None;
+    }
+    let count = u32::from_be_bytes([header[8], header[9], header[10], header[11]]);
+    Some(count as usize)
+}
+
 /// Fallback git status collection using the git2 library, used when the `git` CLI is not found.
 fn collect_git_status_git2(mut snapshot: GitStatusSnapshot, repo_path: &std::path::Path) -> GitStatusSnapshot {
     use crate::status_char;
@@ -201,11 +366,24 @@ fn collect_git_status_git2(mut snapshot: GitStatusSnapshot, repo_path: &std::pat
     };
 
     snapshot.is_git_repo = true;
+    snapshot.operation = RepoOperation::from_state(repo.state());
+    snapshot.tracked_files = index_entry_count(&repo.path().join("index"));
 
     if let Ok(head) = repo.head() {
         if let Some(name) = head.shorthand() {
             snapshot.branch_name = name.to_string();
         }
+        snapshot.head_oid = head.target().map(|oid| oid.to_string());
+        let upstream = repo
+            .find_branch(&snapshot.branch_name, git2::BranchType::Local)
+            .ok()
+            .and_then(|b| b.upstream().ok());
+        if let Some(upstream) = upstream {
+            snapshot.upstream = upstream.name().ok().flatten().map(str::to_string);
+            if let (Some(local), Some(remote)) = (head.target(), upstream.get().target()) {
+                snapshot.ahead_behind = repo.graph_ahead_behind(local, remote).ok();
+            }
+        }
     }
 
     let mut opts = StatusOptions::new();
@@ -217,46 +395,118 @@ fn collect_git_status_git2(mut snapshot: GitStatusSnapshot, repo_path: &std::pat
         .renames_head_to_index(false)
         .renames_index_to_workdir(false);
 
-    if let Ok(statuses) = repo.statuses(Some(&mut opts)) {
-        for entry in statuses.iter() {
-            let path = entry.path().unwrap_or("").to_string();
-            let status = entry.status();
+    let statuses = match repo.statuses(Some(&mut opts)) {
+        Ok(statuses) => statuses,
+        Err(err) => {
+            snapshot.error = Some(err.message().to_string());
+            return snapshot;
+        }
+    };
+    for entry in statuses.iter() {
+        let path = entry.path().unwrap_or("").to_string();
+        let status = entry.status();
 
-            if status.intersects(Status::INDEX_NEW | Status::INDEX_MODIFIED | Status::INDEX_DELETED | Status::INDEX_RENAMED) {
-                snapshot.staged.push(FileEntry {
-                    path: path.clone(),
-                    status: status_char(status, true),
-                    is_staged: true,
-                });
-            }
-            if status.intersects(Status::WT_MODIFIED | Status::WT_DELETED | Status::WT_RENAMED) {
-                snapshot.unstaged.push(FileEntry {
-                    path: path.clone(),
-                    status: status_char(status, false),
-                    is_staged: false,
-                });
-            }
-            if status.contains(Status::WT_NEW) {
-                snapshot.untracked.push(FileEntry {
-                    path,
-                    status: "?".to_string(),
-                    is_staged: false,
-                });
+        if status.contains(Status::CONFLICTED) {
+            snapshot.conflicted.push(FileEntry {
+                path,
+                status: "U".to_string(),
+                is_staged: false,
+            });
+            continue;
+        }
+        if status.intersects(Status::INDEX_NEW | Status::INDEX_MODIFIED | Status::INDEX_DELETED | Status::INDEX_RENAMED) {
+            snapshot.staged.push(FileEntry {
+                path: path.clone(),
+                status: status_char(status, true),
+                is_staged: true,
+            });
+        }
+        if status.intersects(Status::WT_MODIFIED | Status::WT_DELETED | Status::WT_RENAMED) {
+            snapshot.unstaged.push(FileEntry {
+                path: path.clone(),
+                status: status_char(status, false),
+                is_staged: false,
+            });
+        }
+        if status.contains(Status::WT_NEW) {
+            snapshot.untracked.push(FileEntry {
+                path,
+                status: "?".to_string(),
+                is_staged: false,
+            });
+        }
+    }
+
+    // Like the CLI path: new directories come back whole, so list their files with one
+    // more status call and keep the directories for grouping
+    let new_dirs: Vec<String> = snapshot
+        .untracked
+        .iter()
+        .filter(|f| f.path.ends_with('/'))
+        .map(|f| f.path.clone())
+        .collect();
+    if !new_dirs.is_empty() {
+        let mut opts = StatusOptions::new();
+        opts.include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(false)
+            .exclude_submodules(true);
+        for dir in &new_dirs {
+            opts.pathspec(dir);
+        }
+        if let Ok(statuses) = repo.statuses(Some(&mut opts)) {
+            snapshot.untracked.retain(|f| !f.path.ends_with('/'));
+            for entry in statuses
+                .iter()
+                .filter(|e| e.status().contains(Status::WT_NEW))
+            {
+                if let Some(path) = entry.path() {
+                    snapshot.untracked.push(FileEntry {
+                        path: path.to_string(),
+                        status: "?".to_string(),
+                        is_staged: false,
+                    });
+                }
             }
+            snapshot.untracked.sort_by(|a, b| a.path.cmp(&b.path));
+            snapshot.untracked_dirs = new_dirs;
         }
     }
 
     snapshot
 }
 
+/// List a directory for the explorer.
+///
+/// The two filters are independent:
+///
+/// | show_hidden | show_ignored | `.env` | `dist/` (ignored) | `.cache/` (hidden + ignored) |
+/// |-------------|--------------|--------|-------------------|------------------------------|
+/// | false       | false        | no     | no                | no                           |
+/// | true        | false        | yes    | no                | no                           |
+/// | false       | true         | no     | yes               | no                           |
+/// | true        | true         | yes    | yes               | yes                          |
+///
+/// "Hidden" is purely a dotfile check. "Ignored" consults git's ignore rules
+/// (.gitignore, .git/info/exclude, core.excludesfile) and only applies inside a
+/// repository. `node_modules` and `target` are always skipped regardless.
 pub(crate) fn collect_file_tree(
     tab_id: usize,
     current_dir: PathBuf,
     show_hidden: bool,
+    show_ignored: bool,
 ) -> FileTreeSnapshot {
     let started = Instant::now();
-    let mut dirs: Vec<FileTreeEntry> = Vec::new();
-    let mut files: Vec<FileTreeEntry> = Vec::new();
+    let mut tree: Vec<FileTreeEntry> = Vec::new();
+    let dir_missing = !current_dir.is_dir();
+
+    // Only open the repo when we actually need ignore rules
+    let repo = if show_ignored {
+        None
+    } else {
+        Repository::discover(&current_dir).ok()
+    };
+    let workdir = repo.as_ref().and_then(|r| r.workdir().map(|w| w.to_path_buf()));
 
     if let Ok(entries) = std::fs::read_dir(&current_dir) {
         for entry in entries.flatten() {
@@ -266,44 +516,170 @@ pub(crate) fn collect_file_tree(
             if name == "node_modules" || name == "target" {
                 continue;
             }
-
-            let is_dir = path.is_dir();
-            let entry = FileTreeEntry { name, path, is_dir };
-            if is_dir {
-                dirs.push(entry);
-            } else {
-                files.push(entry);
+            if !show_hidden && name.starts_with('.') {
+                continue;
+            }
+            if let (Some(repo), Some(workdir)) = (repo.as_ref(), workdir.as_ref()) {
+                let is_ignored = path
+                    .strip_prefix(workdir)
+                    .ok()
+                    .and_then(|rel| repo.is_path_ignored(rel).ok())
+                    .unwrap_or(false);
+                if is_ignored {
+                    continue;
+                }
             }
+
+            // Follows symlinks, so a linked file sorts by its target
+            let metadata = std::fs::metadata(&path).ok();
+            let is_dir = metadata.as_ref().is_some_and(|m| m.is_dir());
+            let symlink_target = entry
+                .file_type()
+                .is_ok_and(|file_type| file_type.is_symlink())
+                .then(|| std::fs::read_link(&path).unwrap_or_default());
+            tree.push(FileTreeEntry {
+                name,
+                path,
+                is_dir,
+                modified: metadata.as_ref().and_then(|m| m.modified().ok()),
+                size: metadata.filter(|_| !is_dir).map_or(0, |m| m.len()),
+                symlink_target,
+            });
         }
     }
 
-    dirs.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-    files.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-    dirs.extend(files);
+    let (sort, dirs_first) = explorer_order();
+    sort_file_tree(&mut tree, sort, dirs_first);
 
     let snapshot = FileTreeSnapshot {
         tab_id,
         current_dir,
-        entries: dirs,
+        entries: tree,
+        dir_missing,
     };
 
     perf_log!(
-        "file_tree tab={} dir={} entries={} hidden={} took={}ms",
+        "file_tree tab={} dir={} entries={} hidden={} ignored={} took={}ms",
         tab_id,
         snapshot.current_dir.display(),
         snapshot.entries.len(),
         show_hidden,
+        show_ignored,
         started.elapsed().as_millis()
     );
 
     snapshot
 }
 
+/// Search file contents under `root`, sending one `GrepMessage::File` per matching
+/// file as it is found and a final `Done`. The walk honours .gitignore and skips
+/// hidden files like ripgrep does; `node_modules` and `target` are always skipped.
+/// Runs until `MAX_GREP_MATCHES` lines matched, `cancel` is set, or the receiver drops.
+pub(crate) fn grep_workspace(
+    root: PathBuf,
+    query: String,
+    options: SearchOptions,
+    tx: tokio::sync::mpsc::UnboundedSender<GrepMessage>,
+    cancel: Arc<AtomicBool>,
+) {
+    let started = Instant::now();
+    let matcher = match options.build_regex(&query) {
+        Ok(matcher) => matcher,
+        Err(e) => {
+            let _ = tx.send(GrepMessage::Error(e));
+            return;
+        }
+    };
+
+    let walker = ignore::WalkBuilder::new(&root)
+        .filter_entry(|entry| {
+            let name = entry.file_name();
+            name != "node_modules" && name != "target"
+        })
+        .build();
+
+    // Files too large for the viewer to load in full aren't searched either
+    let max_file_bytes = file_load_limits().full_text_max_bytes;
+    let mut files_searched = 0usize;
+    let mut total_matches = 0usize;
+    let mut truncated = false;
+    for entry in walker.flatten() {
+        if cancel.load(Ordering::Relaxed) {
+            return;
+        }
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let too_large = entry
+            .metadata()
+            .map(|m| m.len() > max_file_bytes)
+            .unwrap_or(true);
+        if too_large {
+            continue;
+        }
+        let Ok(bytes) = std::fs::read(entry.path()) else {
+            continue;
+        };
+        // Same binary sniff as git: a NUL in the first 8K
+        if bytes.iter().take(8000).any(|b| *b == 0) {
+            continue;
+        }
+        files_searched += 1;
+
+        let content = String::from_utf8_lossy(&bytes);
+        let mut matches = Vec::new();
+        for (idx, line) in content.lines().enumerate() {
+            if !matcher.is_match(line) {
+                continue;
+            }
+            matches.push(GrepLineMatch {
+                line: idx + 1,
+                preview: line.trim().chars().take(MAX_GREP_PREVIEW_CHARS).collect(),
+            });
+            total_matches += 1;
+            if total_matches >= MAX_GREP_MATCHES {
+                truncated = true;
+                break;
+            }
+        }
+
+        if !matches.is_empty() {
+            let file = GrepFileMatches {
+                path: entry.path().to_path_buf(),
+                matches,
+            };
+            if tx.send(GrepMessage::File(file)).is_err() {
+                return;
+            }
+        }
+        if truncated {
+            break;
+        }
+    }
+
+    perf_log!(
+        "grep root={} files={} matches={} truncated={} took={}ms",
+        root.display(),
+        files_searched,
+        total_matches,
+        truncated,
+        started.elapsed().as_millis()
+    );
+    let _ = tx.send(GrepMessage::Done { truncated });
+}
+
+/// Diff of one file: HEAD→index when `is_staged`, index→workdir otherwise, or
+/// against `base_ref` (see [`base_ref_diff`]) when comparing against a branch,
+/// tag, commit or range.
+/// Stops early, with whatever lines it has, once `cancel` is set; the caller
+/// discards that partial snapshot.
 pub(crate) fn collect_diff(
     tab_id: usize,
     repo_path: PathBuf,
     file_path: String,
     is_staged: bool,
+    base_ref: Option<String>,
+    cancel: &AtomicBool,
 ) -> DiffSnapshot {
     let started = Instant::now();
     let mut lines = Vec::new();
@@ -312,6 +688,7 @@ pub(crate) fn collect_diff(
             tab_id,
             file_path,
             is_staged,
+            base_ref,
             lines,
             diff_syntax_lines: None,
             diff_syntax_notice: None,
@@ -327,15 +704,17 @@ pub(crate) fn collect_diff(
         return snapshot;
     };
 
-    let is_untracked = repo
-        .statuses(None)
-        .ok()
-        .map(|statuses| {
-            statuses.iter().any(|e| {
-                e.path() == Some(file_path.as_str()) && e.status().contains(Status::WT_NEW)
+    // Against a base ref an untracked file may still differ from a committed version
+    let is_untracked = base_ref.is_none()
+        && repo
+            .statuses(None)
+            .ok()
+            .map(|statuses| {
+                statuses.iter().any(|e| {
+                    e.path() == Some(file_path.as_str()) && e.status().contains(Status::WT_NEW)
+                })
             })
-        })
-        .unwrap_or(false);
+            .unwrap_or(false);
 
     if is_untracked {
         let full_path = repo_path.join(&file_path);
@@ -378,6 +757,7 @@ pub(crate) fn collect_diff(
             tab_id,
             file_path,
             is_staged,
+            base_ref,
             lines,
             diff_syntax_lines: None,
             diff_syntax_notice: None,
@@ -395,7 +775,10 @@ pub(crate) fn collect_diff(
 
     let mut diff_opts = DiffOptions::new();
     diff_opts.pathspec(&file_path);
-    let diff = if is_staged {
+    let diff = if let Some(base) = &base_ref {
+        diff_opts.show_untracked_content(true);
+        base_ref_diff(&repo, base, &mut diff_opts)
+    } else if is_staged {
         let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
         repo.diff_tree_to_index(head_tree.as_ref(), None, Some(&mut diff_opts))
     } else {
@@ -448,15 +831,18 @@ pub(crate) fn collect_diff(
                 }),
                 _ => {}
             }
-            true
+            !cancel.load(Ordering::Relaxed)
         });
-        add_word_diffs_to_lines(&mut lines);
+        if !cancel.load(Ordering::Relaxed) {
+            add_word_diffs_to_lines(&mut lines);
+        }
     }
 
     let snapshot = DiffSnapshot {
         tab_id,
         file_path,
         is_staged,
+        base_ref,
         lines,
         diff_syntax_lines: None,
         diff_syntax_notice: None,
@@ -474,157 +860,1678 @@ pub(crate) fn collect_diff(
     snapshot
 }
 
-pub(crate) fn collect_file_load(
+/// Files that differ from `base_ref` (see [`base_ref_diff`]), for reviewing
+/// everything a branch or range of commits changed.
+pub(crate) fn collect_base_diff_files(
     tab_id: usize,
-    path: PathBuf,
-    is_dark_theme: bool,
-) -> FileLoadSnapshot {
+    repo_path: PathBuf,
+    base_ref: String,
+) -> BaseDiffSnapshot {
     let started = Instant::now();
-    let mut snapshot = FileLoadSnapshot {
-        tab_id,
-        path: path.clone(),
-        file_content: String::new(),
-        image_path: None,
-        webview_content: None,
-        file_preview_notice: None,
-        syntax_highlight_lines: None,
-        syntax_highlight_notice: None,
-        file_signature: None,
-    };
+    let result = base_diff_files(&repo_path, &base_ref).map_err(|e| e.message().to_string());
 
-    let file_metadata = std::fs::metadata(&path).ok();
-    let file_size = file_metadata.as_ref().map(|m| m.len()).unwrap_or(0);
-    snapshot.file_signature = file_metadata.as_ref().and_then(|metadata| {
-        let modified_unix_nanos = metadata
-            .modified()
-            .ok()?
-            .duration_since(UNIX_EPOCH)
-            .ok()?
-            .as_nanos();
-        Some(FileVersionSignature {
-            modified_unix_nanos,
-            file_len: metadata.len(),
-        })
-    });
+    perf_log!(
+        "base_diff tab={} base={} files={} took={}ms",
+        tab_id,
+        base_ref,
+        result.as_ref().map(|files| files.len()).unwrap_or(0),
+        started.elapsed().as_millis()
+    );
 
-    #[cfg(feature = "excalidraw")]
-    if excalidraw::is_excalidraw_file(&path) {
-        if file_size > MAX_INLINE_WEBVIEW_BYTES {
-            snapshot.file_preview_notice = Some(format!(
-                "Inline preview skipped for large Excalidraw file ({}). Click \"View in Browser\".",
-                format_bytes(file_size)
-            ));
-            perf_log!(
-                "file_load tab={} path={} kind=excalidraw_skip size={}B text={}B webview={}B notice={} took={}ms",
-                tab_id,
-                path.display(),
-                file_size,
-                snapshot.file_content.len(),
-                snapshot.webview_content.as_ref().map(|s| s.len()).unwrap_or(0),
-                snapshot.file_preview_notice.is_some(),
-                started.elapsed().as_millis()
-            );
-            return snapshot;
-        }
-        if let Ok(content) = std::fs::read_to_string(&path) {
-            if excalidraw::validate_excalidraw(&content) {
-                snapshot.webview_content =
-                    Some(excalidraw::render_excalidraw_html(&content, is_dark_theme));
-            }
-        }
-        perf_log!(
-            "file_load tab={} path={} kind=excalidraw_inline size={}B text={}B webview={}B notice={} took={}ms",
-            tab_id,
-            path.display(),
-            file_size,
-            snapshot.file_content.len(),
-            snapshot.webview_content.as_ref().map(|s| s.len()).unwrap_or(0),
-            snapshot.file_preview_notice.is_some(),
-            started.elapsed().as_millis()
-        );
-        return snapshot;
+    BaseDiffSnapshot {
+        tab_id,
+        base_ref,
+        result,
     }
+}
 
-    if TabState::is_markdown_file(&path) {
-        if file_size > MAX_INLINE_WEBVIEW_BYTES {
-            snapshot.file_preview_notice = Some(format!(
-                "Inline preview skipped for large Markdown file ({}). Click \"View in Browser\".",
-                format_bytes(file_size)
-            ));
-            perf_log!(
-                "file_load tab={} path={} kind=markdown_skip size={}B text={}B webview={}B notice={} took={}ms",
-                tab_id,
-                path.display(),
-                file_size,
-                snapshot.file_content.len(),
-                snapshot.webview_content.as_ref().map(|s| s.len()).unwrap_or(0),
-                snapshot.file_preview_notice.is_some(),
-                started.elapsed().as_millis()
-            );
-            return snapshot;
-        }
-        if let Ok(content) = std::fs::read_to_string(&path) {
-            snapshot.webview_content =
-                Some(markdown::render_markdown_to_html(&content, is_dark_theme));
-        }
-    } else if TabState::is_html_file(&path) {
-        if file_size > MAX_INLINE_WEBVIEW_BYTES {
-            snapshot.file_preview_notice = Some(format!(
-                "Inline preview skipped for large HTML file ({}). Click \"View in Browser\".",
-                format_bytes(file_size)
-            ));
-            perf_log!(
-                "file_load tab={} path={} kind=html_skip size={}B text={}B webview={}B notice={} took={}ms",
-                tab_id,
-                path.display(),
-                file_size,
-                snapshot.file_content.len(),
-                snapshot.webview_content.as_ref().map(|s| s.len()).unwrap_or(0),
-                snapshot.file_preview_notice.is_some(),
-                started.elapsed().as_millis()
-            );
-            return snapshot;
-        }
-        if let Ok(content) = std::fs::read_to_string(&path) {
-            snapshot.webview_content = Some(content);
-        }
-    } else if TabState::is_image_file(&path) {
-        snapshot.image_path = Some(path.clone());
-    } else if file_size > MAX_FULL_TEXT_LOAD_BYTES {
-        if let Ok(preview) =
-            read_text_preview(&path, LARGE_TEXT_PREVIEW_BYTES, LARGE_TEXT_PREVIEW_LINES)
-        {
-            snapshot.file_content = preview;
-        } else if let Ok(content) = std::fs::read_to_string(&path) {
-            snapshot.file_content = content;
-        }
-        snapshot.file_preview_notice = Some(format!(
-            "Large file ({}): showing first {} lines (~{} KB).",
-            format_bytes(file_size),
-            LARGE_TEXT_PREVIEW_LINES,
-            LARGE_TEXT_PREVIEW_BYTES / 1024
-        ));
-    } else if let Ok(content) = std::fs::read_to_string(&path) {
-        snapshot.file_content = content;
-    }
+fn base_diff_files(
+    repo_path: &std::path::Path,
+    base_ref: &str,
+) -> Result<Vec<FileEntry>, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let diff = base_ref_diff(&repo, base_ref, &mut DiffOptions::new())?;
 
-    let kind = if snapshot.image_path.is_some() {
-        "image"
-    } else if snapshot.webview_content.is_some() {
-        "inline_webview"
-    } else if snapshot.file_preview_notice.is_some() {
-        "text_preview"
+    let mut files: Vec<FileEntry> = diff
+        .deltas()
+        .filter_map(|delta| {
+            let status = match delta.status() {
+                git2::Delta::Added | git2::Delta::Untracked => "A",
+                git2::Delta::Deleted => "D",
+                git2::Delta::Renamed => "R",
+                _ => "M",
+            };
+            let path = delta.new_file().path().or_else(|| delta.old_file().path())?;
+            Some(FileEntry {
+                path: path.to_string_lossy().to_string(),
+                status: status.to_string(),
+                is_staged: false,
+            })
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Diff for a "compare with" spec. A single ref is compared with the working
+/// tree, staged and unstaged changes together. `A..B` compares the two commits
+/// and `A...B` compares `B` with where it forked from `A`, as `git diff` does.
+fn base_ref_diff<'r>(
+    repo: &'r Repository,
+    spec: &str,
+    opts: &mut DiffOptions,
+) -> Result<git2::Diff<'r>, git2::Error> {
+    if !spec.contains("..") {
+        let tree = repo.revparse_single(spec)?.peel_to_tree()?;
+        opts.include_untracked(true).recurse_untracked_dirs(true);
+        return repo.diff_tree_to_workdir_with_index(Some(&tree), Some(opts));
+    }
+    let range = repo.revparse(spec)?;
+    let (Some(from), Some(to)) = (range.from(), range.to()) else {
+        return Err(git2::Error::from_str("expected a range like A..B or A...B"));
+    };
+    let to = to.peel_to_commit()?;
+    let from = from.peel_to_commit()?;
+    let from = if range.mode().contains(git2::RevparseMode::MERGE_BASE) {
+        repo.find_commit(repo.merge_base(from.id(), to.id())?)?
     } else {
-        "text"
+        from
     };
+    repo.diff_tree_to_tree(Some(&from.tree()?), Some(&to.tree()?), Some(opts))
+}
+
+/// Blame a single line on the "old" side of a diff.
+///
+/// For staged diffs the old side is HEAD, so the line number maps directly onto
+/// a HEAD blame. For unstaged diffs the old side is the index; when the index
+/// differs from HEAD we blame the index contents as a buffer on top of HEAD so
+/// line numbers stay aligned.
+pub(crate) fn collect_line_blame(
+    tab_id: usize,
+    repo_path: PathBuf,
+    file_path: String,
+    is_staged: bool,
+    line: u32,
+) -> LineBlameSnapshot {
+    let started = Instant::now();
+    let result = blame_line(&repo_path, &file_path, is_staged, line);
+
     perf_log!(
-        "file_load tab={} path={} kind={} size={}B text={}B webview={}B preview_notice={} syntax_notice={} took={}ms",
+        "line_blame tab={} file={} line={} ok={} took={}ms",
         tab_id,
-        path.display(),
-        kind,
-        file_size,
-        snapshot.file_content.len(),
-        snapshot
+        file_path,
+        line,
+        result.is_ok(),
+        started.elapsed().as_millis()
+    );
+
+    LineBlameSnapshot {
+        tab_id,
+        file_path,
+        line,
+        result,
+    }
+}
+
+fn blame_line(
+    repo_path: &std::path::Path,
+    file_path: &str,
+    is_staged: bool,
+    line: u32,
+) -> Result<LineBlame, String> {
+    let repo = Repository::open(repo_path).map_err(|e| e.message().to_string())?;
+    let rel_path = std::path::Path::new(file_path);
+
+    // Only unstaged diffs can have an old side that differs from HEAD.
+    let index_blob = if is_staged {
+        None
+    } else {
+        let head_oid = repo
+            .head()
+            .ok()
+            .and_then(|h| h.peel_to_tree().ok())
+            .and_then(|tree| tree.get_path(rel_path).ok())
+            .map(|entry| entry.id());
+        repo.index()
+            .ok()
+            .and_then(|index| index.get_path(rel_path, 0))
+            .filter(|entry| Some(entry.id) != head_oid)
+            .and_then(|entry| repo.find_blob(entry.id).ok())
+    };
+
+    let commit_id = if let Some(blob) = index_blob {
+        let base = repo
+            .blame_file(rel_path, None)
+            .map_err(|e| e.message().to_string())?;
+        let blame = base
+            .blame_buffer(blob.content())
+            .map_err(|e| e.message().to_string())?;
+        let commit_id = blame
+            .get_line(line as usize)
+            .map(|hunk| hunk.final_commit_id());
+        commit_id
+    } else {
+        let mut opts = BlameOptions::new();
+        opts.min_line(line as usize).max_line(line as usize);
+        let blame = repo
+            .blame_file(rel_path, Some(&mut opts))
+            .map_err(|e| e.message().to_string())?;
+        let commit_id = blame
+            .get_line(line as usize)
+            .map(|hunk| hunk.final_commit_id());
+        commit_id
+    };
+
+    let Some(commit_id) = commit_id else {
+        return Err(format!("No blame information for line {}", line));
+    };
+    if commit_id.is_zero() {
+        return Err("Not committed yet".to_string());
+    }
+
+    let commit = repo
+        .find_commit(commit_id)
+        .map_err(|e| e.message().to_string())?;
+    let author = commit.author();
+    let date = chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+        .map(|dt| {
+            dt.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default();
+    let id = commit_id.to_string();
+
+    Ok(LineBlame {
+        short_id: id[..id.len().min(8)].to_string(),
+        author: author.name().unwrap_or("unknown").to_string(),
+        date,
+        summary: commit.summary().unwrap_or("").to_string(),
+    })
+}
+
+/// Most recent commit on HEAD's first-parent history that changed `path`.
+/// `Ok(None)` means the file isn't in HEAD yet.
+pub(crate) fn collect_file_last_commit(
+    tab_id: usize,
+    repo_path: PathBuf,
+    path: PathBuf,
+    signature: Option<FileVersionSignature>,
+    head_oid: Option<String>,
+) -> FileLastCommitSnapshot {
+    let started = Instant::now();
+    let result = file_last_commit(&repo_path, &path);
+
+    perf_log!(
+        "file_last_commit tab={} file={} ok={} took={}ms",
+        tab_id,
+        path.display(),
+        result.is_ok(),
+        started.elapsed().as_millis()
+    );
+
+    FileLastCommitSnapshot {
+        tab_id,
+        path,
+        signature,
+        head_oid,
+        result,
+    }
+}
+
+fn file_last_commit(
+    repo_path: &std::path::Path,
+    path: &std::path::Path,
+) -> Result<Option<FileLastCommit>, String> {
+    let repo = Repository::open(repo_path).map_err(|e| e.message().to_string())?;
+    let rel_path = path
+        .strip_prefix(repo_path)
+        .map_err(|_| "File is outside the repository".to_string())?;
+    let Some(head) = repo.head().ok().and_then(|h| h.peel_to_commit().ok()) else {
+        return Ok(None);
+    };
+    let blob_id = |commit: &git2::Commit| {
+        commit
+            .tree()
+            .ok()
+            .and_then(|tree| tree.get_path(rel_path).ok())
+            .map(|entry| entry.id())
+    };
+    if blob_id(&head).is_none() {
+        return Ok(None);
+    }
+
+    let mut walk = repo.revwalk().map_err(|e| e.message().to_string())?;
+    walk.push(head.id()).map_err(|e| e.message().to_string())?;
+    walk.simplify_first_parent()
+        .map_err(|e| e.message().to_string())?;
+    for oid in walk.take(LAST_COMMIT_WALK_LIMIT) {
+        let oid = oid.map_err(|e| e.message().to_string())?;
+        let commit = repo.find_commit(oid).map_err(|e| e.message().to_string())?;
+        let parent_blob = commit.parent(0).ok().and_then(|parent| blob_id(&parent));
+        if blob_id(&commit) == parent_blob {
+            continue;
+        }
+        let id = oid.to_string();
+        return Ok(Some(FileLastCommit {
+            short_id: id[..id.len().min(8)].to_string(),
+            author: commit.author().name().unwrap_or("unknown").to_string(),
+            time: commit.time().seconds(),
+            summary: commit.summary().unwrap_or("").to_string(),
+        }));
+    }
+    Err(format!(
+        "No change found in the last {} commits",
+        LAST_COMMIT_WALK_LIMIT
+    ))
+}
+
+/// The repo's `.gitmessage`, then the file named by git's `commit.template`, falling
+/// back to the `commit_template` from config.
+pub(crate) fn collect_commit_template(
+    repo_path: PathBuf,
+    fallback: Option<String>,
+) -> Result<Option<String>, String> {
+    let path = repo_path.join(".gitmessage");
+    match std::fs::read_to_string(&path) {
+        Ok(template) => return Ok(Some(template)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(format!("Couldn't read {}: {}", path.display(), err)),
+    }
+    // `~/` is expanded by git2; a relative path is taken from the repo root
+    let configured = Repository::open(&repo_path)
+        .and_then(|repo| repo.config())
+        .and_then(|config| config.get_path("commit.template"));
+    match configured {
+        Ok(path) => {
+            let path = repo_path.join(path);
+            std::fs::read_to_string(&path)
+                .map(Some)
+                .map_err(|err| format!("Couldn't read {}: {}", path.display(), err))
+        }
+        Err(_) => Ok(fallback),
+    }
+}
+
+/// Message of the HEAD commit, body included, used to pre-fill the commit box when
+/// amending. Returns an explanatory error when there is nothing sensible to amend.
+pub(crate) fn collect_head_commit_message(repo_path: PathBuf) -> Result<String, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.message().to_string())?;
+    let head = repo
+        .head()
+        .ok()
+        .and_then(|h| h.peel_to_commit().ok())
+        .ok_or_else(|| "No commit to amend yet".to_string())?;
+    if head.parent_count() > 1 {
+        return Err(AMEND_MERGE_ERROR.to_string());
+    }
+    Ok(head.message().unwrap_or("").trim_end().to_string())
+}
+
+/// Commit the current index. With `amend`, HEAD is rewritten in place: the new
+/// commit keeps HEAD's parents and takes its tree from the index. Otherwise a
+/// resolved merge, cherry-pick or revert is concluded by the new commit.
+///
+/// `author` (the workspace's override) signs the commit instead of git's
+/// configured identity; when amending it becomes the committer and the original
+/// author is kept, as `git commit --amend` does.
+pub(crate) fn perform_commit(
+    tab_id: usize,
+    repo_path: PathBuf,
+    message: String,
+    amend: bool,
+    author: Option<GitAuthor>,
+) -> GitOpSnapshot {
+    let started = Instant::now();
+    let result = commit_index(&repo_path, message.trim(), amend, author.as_ref());
+
+    perf_log!(
+        "commit tab={} amend={} ok={} took={}ms",
+        tab_id,
+        amend,
+        result.is_ok(),
+        started.elapsed().as_millis()
+    );
+
+    GitOpSnapshot {
+        tab_id,
+        kind: GitOpKind::Commit,
+        result,
+    }
+}
+
+fn commit_index(
+    repo_path: &std::path::Path,
+    message: &str,
+    amend: bool,
+    author: Option<&GitAuthor>,
+) -> Result<String, String> {
+    if message.is_empty() {
+        return Err("Commit message is empty".to_string());
+    }
+    let repo = Repository::open(repo_path).map_err(|e| e.message().to_string())?;
+    let mut index = repo.index().map_err(|e| e.message().to_string())?;
+    let tree_id = index.write_tree().map_err(|e| e.message().to_string())?;
+    let tree = repo.find_tree(tree_id).map_err(|e| e.message().to_string())?;
+    let head = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+
+    let commit_id = if amend {
+        let head = head.ok_or_else(|| "No commit to amend yet".to_string())?;
+        if head.parent_count() > 1 {
+            return Err(AMEND_MERGE_ERROR.to_string());
+        }
+        // A fresh committer and timestamp, as `git commit --amend` gives
+        let committer = match author {
+            Some(author) => author_signature(author)?,
+            None => repo.signature().map_err(|e| e.message().to_string())?,
+        };
+        head.amend(Some("HEAD"), None, Some(&committer), None, Some(message), Some(&tree))
+            .map_err(|e| e.message().to_string())?
+    } else {
+        let state = repo.state();
+        // A merge resolved in favour of HEAD still needs its merge commit
+        if state != git2::RepositoryState::Merge
+            && head.as_ref().is_some_and(|h| h.tree_id() == tree_id)
+        {
+            return Err("Nothing staged to commit".to_string());
+        }
+        let sig = match author {
+            Some(author) => author_signature(author)?,
+            None => repo.signature().map_err(|e| e.message().to_string())?,
+        };
+        let mut parents: Vec<git2::Commit> = head.into_iter().collect();
+        parents.extend(merge_head_commits(&repo, state)?);
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        let commit_id = repo
+            .commit(Some("HEAD"), &sig, &sig, message, &tree, &parent_refs)
+            .map_err(|e| e.message().to_string())?;
+        conclude_operation(&repo, state)?;
+        commit_id
+    };
+
+    let id = commit_id.to_string();
+    let summary = message.lines().next().unwrap_or("");
+    Ok(format!(
+        "{} {} {}",
+        if amend { "Amended" } else { "Committed" },
+        &id[..id.len().min(8)],
+        summary
+    ))
+}
+
+fn author_signature(author: &GitAuthor) -> Result<git2::Signature<'static>, String> {
+    git2::Signature::now(&author.name, &author.email)
+        .map_err(|e| format!("Invalid workspace git author: {}", e.message()))
+}
+
+/// CPU and memory of a console's process group (the shell plus everything it
+/// started), from `ps`; `None` once the group is gone or when `ps` isn't available.
+pub(crate) fn collect_process_usage(pgid: u32) -> Option<ProcessUsage> {
+    let output = std::process::Command::new("ps")
+        .args(["-A", "-o", "pgid=,pcpu=,rss="])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    sum_process_group_usage(&String::from_utf8_lossy(&output.stdout), pgid)
+}
+
+/// The identity git would sign commits with in this repo, as "Name <email>";
+/// `None` when `user.name`/`user.email` aren't configured.
+pub(crate) fn collect_git_identity(repo_path: PathBuf) -> Option<String> {
+    let repo = Repository::open(&repo_path).ok()?;
+    let sig = repo.signature().ok()?;
+    Some(format!("{} <{}>", sig.name()?, sig.email()?))
+}
+
+/// Stage all, unstage all, or discard all working-tree changes.
+///
+/// Discard resets tracked files to the index and deletes the given untracked
+/// files (the ones the sidebar was showing); staged changes are left alone.
+pub(crate) fn perform_bulk_index_op(
+    tab_id: usize,
+    repo_path: PathBuf,
+    kind: GitOpKind,
+    modified: Vec<String>,
+    untracked: Vec<String>,
+) -> GitOpSnapshot {
+    let started = Instant::now();
+    let result = bulk_index_op(&repo_path, kind, &modified, &untracked);
+
+    perf_log!(
+        "bulk_index_op tab={} kind={:?} ok={} took={}ms",
+        tab_id,
+        kind,
+        result.is_ok(),
+        started.elapsed().as_millis()
+    );
+
+    GitOpSnapshot {
+        tab_id,
+        kind,
+        result,
+    }
+}
+
+fn bulk_index_op(
+    repo_path: &std::path::Path,
+    kind: GitOpKind,
+    modified: &[String],
+    untracked: &[String],
+) -> Result<String, String> {
+    let repo = Repository::open(repo_path).map_err(|e| e.message().to_string())?;
+    match kind {
+        GitOpKind::StageAll => {
+            let mut index = repo.index().map_err(|e| e.message().to_string())?;
+            index
+                .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+                .map_err(|e| e.message().to_string())?;
+            // add_all doesn't record deletions; update_all does
+            index
+                .update_all(["*"].iter(), None)
+                .map_err(|e| e.message().to_string())?;
+            index.write().map_err(|e| e.message().to_string())?;
+            Ok("Staged all changes".to_string())
+        }
+        GitOpKind::UnstageAll => {
+            match repo.head().ok().and_then(|h| h.peel_to_commit().ok()) {
+                Some(head) => repo
+                    .reset_default(Some(head.as_object()), ["*"].iter())
+                    .map_err(|e| e.message().to_string())?,
+                None => {
+                    // Unborn branch: nothing to reset to, so empty the index
+                    let mut index = repo.index().map_err(|e| e.message().to_string())?;
+                    index.clear().map_err(|e| e.message().to_string())?;
+                    index.write().map_err(|e| e.message().to_string())?;
+                }
+            }
+            Ok("Unstaged all changes".to_string())
+        }
+        GitOpKind::DiscardAll => {
+            // Checking out with no paths would reset every file, listed or not
+            if !modified.is_empty() {
+                let mut checkout = git2::build::CheckoutBuilder::new();
+                checkout.force();
+                for path in modified {
+                    checkout.path(path.as_str());
+                }
+                repo.checkout_index(None, Some(&mut checkout))
+                    .map_err(|e| e.message().to_string())?;
+            }
+
+            let removed = untracked
+                .iter()
+                .filter(|path| remove_untracked_file(repo_path, path))
+                .count();
+            let failed = untracked.len() - removed;
+            if failed > 0 {
+                return Err(format!(
+                    "Discarded changes, but {} untracked file(s) could not be removed",
+                    failed
+                ));
+            }
+            Ok(format!(
+                "Discarded working tree changes and {} untracked file(s)",
+                removed
+            ))
+        }
+        _ => Err("Unsupported operation".to_string()),
+    }
+}
+
+/// Delete an untracked file, then any directories it leaves empty up to the repo root.
+fn remove_untracked_file(repo_path: &std::path::Path, path: &str) -> bool {
+    let removed = std::fs::remove_file(repo_path.join(path)).is_ok();
+    let mut parent = std::path::Path::new(path).parent();
+    while let Some(dir) = parent.filter(|d| !d.as_os_str().is_empty()) {
+        if std::fs::remove_dir(repo_path.join(dir)).is_err() {
+            break;
+        }
+        parent = dir.parent();
+    }
+    removed
+}
+
+/// Stage, unstage or discard just `paths`, the files multi-selected in the Git list,
+/// in one pass. Discarding deletes untracked files and resets tracked ones to the
+/// index, like Discard All does for everything.
+pub(crate) fn perform_selection_index_op(
+    tab_id: usize,
+    repo_path: PathBuf,
+    kind: GitOpKind,
+    paths: Vec<String>,
+) -> GitOpSnapshot {
+    let started = Instant::now();
+    let result = selection_index_op(&repo_path, kind, &paths);
+
+    perf_log!(
+        "selection_index_op tab={} kind={:?} paths={} ok={} took={}ms",
+        tab_id,
+        kind,
+        paths.len(),
+        result.is_ok(),
+        started.elapsed().as_millis()
+    );
+
+    GitOpSnapshot {
+        tab_id,
+        kind,
+        result,
+    }
+}
+
+fn selection_index_op(
+    repo_path: &std::path::Path,
+    kind: GitOpKind,
+    paths: &[String],
+) -> Result<String, String> {
+    let repo = Repository::open(repo_path).map_err(|e| e.message().to_string())?;
+    match kind {
+        GitOpKind::StageSelected => {
+            let mut index = repo.index().map_err(|e| e.message().to_string())?;
+            for path in paths {
+                let relative = std::path::Path::new(path);
+                // A deleted file is staged by dropping it from the index
+                if repo_path.join(relative).exists() {
+                    index.add_path(relative)
+                } else {
+                    index.remove_path(relative)
+                }
+                .map_err(|e| e.message().to_string())?;
+            }
+            index.write().map_err(|e| e.message().to_string())?;
+            Ok(format!("Staged {} file(s)", paths.len()))
+        }
+        GitOpKind::UnstageSelected => {
+            match repo.head().ok().and_then(|h| h.peel_to_commit().ok()) {
+                Some(head) => repo
+                    .reset_default(Some(head.as_object()), paths.iter().map(String::as_str))
+                    .map_err(|e| e.message().to_string())?,
+                None => {
+                    // Unborn branch: nothing to reset to, so drop them from the index
+                    let mut index = repo.index().map_err(|e| e.message().to_string())?;
+                    for path in paths {
+                        index
+                            .remove_path(std::path::Path::new(path))
+                            .map_err(|e| e.message().to_string())?;
+                    }
+                    index.write().map_err(|e| e.message().to_string())?;
+                }
+            }
+            Ok(format!("Unstaged {} file(s)", paths.len()))
+        }
+        GitOpKind::DiscardSelected => {
+            let (untracked, tracked): (Vec<&String>, Vec<&String>) =
+                paths.iter().partition(|path| {
+                    repo.status_file(std::path::Path::new(path.as_str()))
+                        .is_ok_and(|status| status.contains(Status::WT_NEW))
+                });
+            if !tracked.is_empty() {
+                let mut checkout = git2::build::CheckoutBuilder::new();
+                checkout.force();
+                for path in &tracked {
+                    checkout.path(path.as_str());
+                }
+                repo.checkout_index(None, Some(&mut checkout))
+                    .map_err(|e| e.message().to_string())?;
+            }
+            let failed = untracked
+                .iter()
+                .filter(|path| !remove_untracked_file(repo_path, path))
+                .count();
+            if failed > 0 {
+                return Err(format!(
+                    "Discarded changes, but {} untracked file(s) could not be removed",
+                    failed
+                ));
+            }
+            Ok(format!("Discarded changes to {} file(s)", paths.len()))
+        }
+        _ => Err("Unsupported operation".to_string()),
+    }
+}
+
+/// Remote callbacks that try ssh-agent first, then the configured git credential
+/// helper, then default (e.g. Kerberos) credentials. Each is attempted once so a
+/// rejected credential surfaces as an auth error instead of looping.
+fn remote_callbacks<'a>(config: Option<git2::Config>) -> git2::RemoteCallbacks<'a> {
+    let mut callbacks = git2::RemoteCallbacks::new();
+    let mut tried_agent = false;
+    let mut tried_helper = false;
+    let mut tried_default = false;
+    callbacks.credentials(move |url, username_from_url, allowed| {
+        if allowed.contains(git2::CredentialType::SSH_KEY) && !tried_agent {
+            tried_agent = true;
+            return git2::Cred::ssh_key_from_agent(username_from_url.unwrap_or("git"));
+        }
+        if allowed.contains(git2::CredentialType::USER_PASS_PLAINTEXT) && !tried_helper {
+            tried_helper = true;
+            if let Some(config) = config.as_ref() {
+                return git2::Cred::credential_helper(config, url, username_from_url);
+            }
+        }
+        if allowed.contains(git2::CredentialType::DEFAULT) && !tried_default {
+            tried_default = true;
+            return git2::Cred::default();
+        }
+        Err(git2::Error::from_str("no usable credentials"))
+    });
+    callbacks
+}
+
+/// Record each transfer step in `progress`: objects received on fetch, objects packed
+/// and written on push.
+fn track_remote_progress(callbacks: &mut git2::RemoteCallbacks<'_>, progress: &RemoteProgress) {
+    fn set(progress: &RemoteProgress, line: String) {
+        if let Ok(mut slot) = progress.lock() {
+            *slot = Some(line);
+        }
+    }
+    let fetch = progress.clone();
+    callbacks.transfer_progress(move |stats| {
+        let line = if stats.received_objects() < stats.total_objects() {
+            format!(
+                "Receiving objects {}/{} ({})",
+                stats.received_objects(),
+                stats.total_objects(),
+                format_bytes(stats.received_bytes() as u64)
+            )
+        } else {
+            format!(
+                "Resolving deltas {}/{}",
+                stats.indexed_deltas(),
+                stats.total_deltas()
+            )
+        };
+        set(&fetch, line);
+        true
+    });
+    let pack = progress.clone();
+    callbacks.pack_progress(move |stage, current, total| {
+        let line = match stage {
+            git2::PackBuilderStage::AddingObjects => format!("Counting objects {}", current),
+            git2::PackBuilderStage::Deltafication => {
+                format!("Compressing objects {}/{}", current, total)
+            }
+        };
+        set(&pack, line);
+    });
+    let push = progress.clone();
+    callbacks.push_transfer_progress(move |current, total, bytes| {
+        let line = format!(
+            "Writing objects {}/{} ({})",
+            current,
+            total,
+            format_bytes(bytes as u64)
+        );
+        set(&push, line);
+    });
+}
+
+fn remote_error_message(remote_name: &str, err: &git2::Error) -> String {
+    let is_auth = err.code() == git2::ErrorCode::Auth
+        || err.class() == git2::ErrorClass::Ssh
+        || err.message().contains("credentials");
+    if is_auth {
+        format!(
+            "Authentication failed for '{}': {}. Check ssh-agent or your git credential helper.",
+            remote_name,
+            err.message()
+        )
+    } else {
+        format!("'{}': {}", remote_name, err.message())
+    }
+}
+
+/// The remote tracked by the current branch, falling back to "origin".
+fn current_remote_name(repo: &Repository) -> String {
+    repo.head()
+        .ok()
+        .and_then(|head| head.name().map(str::to_string))
+        .and_then(|refname| repo.branch_upstream_remote(&refname).ok())
+        .and_then(|buf| buf.as_str().map(str::to_string))
+        .unwrap_or_else(|| "origin".to_string())
+}
+
+fn fetch_remote(
+    repo: &Repository,
+    remote_name: &str,
+    progress: &RemoteProgress,
+) -> Result<(), String> {
+    let mut remote = repo
+        .find_remote(remote_name)
+        .map_err(|e| remote_error_message(remote_name, &e))?;
+    let mut callbacks = remote_callbacks(repo.config().ok());
+    track_remote_progress(&mut callbacks, progress);
+    let mut fetch_opts = git2::FetchOptions::new();
+    fetch_opts.remote_callbacks(callbacks);
+    remote
+        .fetch(&[] as &[&str], Some(&mut fetch_opts), None)
+        .map_err(|e| remote_error_message(remote_name, &e))
+}
+
+/// Fetch the current branch's remote, and for `Pull` fast-forward onto the
+/// upstream. Diverged branches are reported rather than merged. `Push` pushes
+/// the current branch (never forced), setting the upstream on first push. Transfer
+/// progress is written to `progress` as it happens.
+pub(crate) fn perform_remote_sync(
+    tab_id: usize,
+    repo_path: PathBuf,
+    kind: GitOpKind,
+    progress: RemoteProgress,
+) -> GitOpSnapshot {
+    let started = Instant::now();
+    let result = remote_sync(&repo_path, kind, &progress);
+
+    perf_log!(
+        "remote_sync tab={} kind={:?} ok={} took={}ms",
+        tab_id,
+        kind,
+        result.is_ok(),
+        started.elapsed().as_millis()
+    );
+
+    GitOpSnapshot {
+        tab_id,
+        kind,
+        result,
+    }
+}
+
+/// The most recent `limit` commits reachable from HEAD or any local branch, newest first.
+/// History of HEAD, or of the local `branch` when one is picked, plus the local
+/// branch names to pick from.
+pub(crate) fn collect_commit_log(
+    tab_id: usize,
+    repo_path: PathBuf,
+    limit: usize,
+    branch: Option<String>,
+) -> CommitLogSnapshot {
+    let started = Instant::now();
+    let result = commit_log(&repo_path, limit, branch.as_deref());
+    let local_branches = Repository::open(&repo_path)
+        .ok()
+        .and_then(|repo| {
+            let branches = repo.branches(Some(git2::BranchType::Local)).ok()?;
+            let mut names: Vec<String> = branches
+                .flatten()
+                .filter_map(|(branch, _)| branch.name().ok().flatten().map(str::to_string))
+                .collect();
+            names.sort();
+            Some(names)
+        })
+        .unwrap_or_default();
+
+    perf_log!(
+        "commit_log tab={} commits={} took={}ms",
+        tab_id,
+        result.as_ref().map(|entries| entries.len()).unwrap_or(0),
+        started.elapsed().as_millis()
+    );
+
+    CommitLogSnapshot {
+        tab_id,
+        local_branches,
+        result,
+    }
+}
+
+fn commit_log(
+    repo_path: &std::path::Path,
+    limit: usize,
+    branch: Option<&str>,
+) -> Result<Vec<CommitLogEntry>, String> {
+    let repo = Repository::open(repo_path).map_err(|e| e.message().to_string())?;
+    let Some(head) = repo.head().ok().and_then(|h| h.target()) else {
+        // Unborn branch: nothing committed yet
+        return Ok(Vec::new());
+    };
+    let tip = match branch {
+        Some(name) => repo
+            .find_branch(name, git2::BranchType::Local)
+            .ok()
+            .and_then(|b| b.get().target())
+            .ok_or_else(|| format!("Branch '{}' not found", name))?,
+        None => head,
+    };
+    let mut revwalk = repo.revwalk().map_err(|e| e.message().to_string())?;
+    revwalk.push(tip).map_err(|e| e.message().to_string())?;
+    revwalk
+        .set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)
+        .map_err(|e| e.message().to_string())?;
+
+    // Local branch names by the commit they point at, for labels
+    let mut branch_tips: std::collections::HashMap<git2::Oid, Vec<String>> =
+        std::collections::HashMap::new();
+    if let Ok(branches) = repo.branches(Some(git2::BranchType::Local)) {
+        for (branch, _) in branches.flatten() {
+            if let (Some(target), Ok(Some(name))) = (branch.get().target(), branch.name()) {
+                branch_tips.entry(target).or_default().push(name.to_string());
+            }
+        }
+    }
+
+    let mut entries = Vec::new();
+    for oid in revwalk.take(limit) {
+        let oid = oid.map_err(|e| e.message().to_string())?;
+        let commit = repo.find_commit(oid).map_err(|e| e.message().to_string())?;
+        let date = chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+            .map(|dt| {
+                dt.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        let id = oid.to_string();
+        entries.push(CommitLogEntry {
+            short_id: id[..id.len().min(8)].to_string(),
+            id,
+            summary: commit.summary().unwrap_or("").to_string(),
+            author: commit.author().name().unwrap_or("unknown").to_string(),
+            date,
+            branches: branch_tips.remove(&oid).unwrap_or_default(),
+            on_head: oid == head || repo.graph_descendant_of(head, oid).unwrap_or(false),
+        });
+    }
+    Ok(entries)
+}
+
+/// Create a branch or lightweight tag pointing at `target` (a full commit id).
+/// Existing refs are never overwritten.
+pub(crate) fn perform_create_ref(
+    tab_id: usize,
+    repo_path: PathBuf,
+    kind: RefKind,
+    name: String,
+    target: String,
+) -> GitOpSnapshot {
+    let started = Instant::now();
+    let result = create_ref(&repo_path, kind, name.trim(), &target);
+
+    perf_log!(
+        "create_ref tab={} kind={:?} name={} ok={} took={}ms",
+        tab_id,
+        kind,
+        name,
+        result.is_ok(),
+        started.elapsed().as_millis()
+    );
+
+    GitOpSnapshot {
+        tab_id,
+        kind: GitOpKind::CreateRef(kind),
+        result,
+    }
+}
+
+fn create_ref(
+    repo_path: &std::path::Path,
+    kind: RefKind,
+    name: &str,
+    target: &str,
+) -> Result<String, String> {
+    let full_name = format!("{}{}", kind.ref_prefix(), name);
+    if name.is_empty() || !git2::Reference::is_valid_name(&full_name) {
+        return Err(format!("'{}' is not a valid {} name", name, kind.noun()));
+    }
+    let repo = Repository::open(repo_path).map_err(|e| e.message().to_string())?;
+    if repo.find_reference(&full_name).is_ok() {
+        return Err(format!("A {} named '{}' already exists", kind.noun(), name));
+    }
+    let commit = git2::Oid::from_str(target)
+        .and_then(|oid| repo.find_commit(oid))
+        .map_err(|e| e.message().to_string())?;
+    match kind {
+        RefKind::Branch => repo.branch(name, &commit, false).map(|_| ()),
+        RefKind::Tag => repo
+            .tag_lightweight(name, commit.as_object(), false)
+            .map(|_| ()),
+    }
+    .map_err(|e| e.message().to_string())?;
+
+    let id = commit.id().to_string();
+    Ok(format!(
+        "Created {} '{}' at {}",
+        kind.noun(),
+        name,
+        &id[..id.len().min(8)]
+    ))
+}
+
+/// Apply `target` (a full commit id) on top of HEAD as a new commit. Conflicts
+/// leave the repository mid cherry-pick so the Git sidebar can show them.
+pub(crate) fn perform_cherry_pick(tab_id: usize, repo_path: PathBuf, target: String) -> GitOpSnapshot {
+    let started = Instant::now();
+    let result = cherry_pick(&repo_path, &target);
+
+    perf_log!(
+        "cherry_pick tab={} commit={} ok={} took={}ms",
+        tab_id,
+        target,
+        result.is_ok(),
+        started.elapsed().as_millis()
+    );
+
+    GitOpSnapshot {
+        tab_id,
+        kind: GitOpKind::CherryPick,
+        result,
+    }
+}
+
+fn cherry_pick(repo_path: &std::path::Path, target: &str) -> Result<String, String> {
+    let repo = Repository::open(repo_path).map_err(|e| e.message().to_string())?;
+    if let Some(operation) = RepoOperation::from_state(repo.state()).git_command() {
+        return Err(format!("Finish or abort the {} in progress first", operation));
+    }
+    let commit = git2::Oid::from_str(target)
+        .and_then(|oid| repo.find_commit(oid))
+        .map_err(|e| e.message().to_string())?;
+    if commit.parent_count() > 1 {
+        return Err("Cherry-picking a merge commit needs a mainline; use the terminal".to_string());
+    }
+    let head = repo
+        .head()
+        .and_then(|h| h.peel_to_commit())
+        .map_err(|e| e.message().to_string())?;
+    let id = commit.id().to_string();
+    let short_id = &id[..id.len().min(8)];
+
+    repo.cherrypick(&commit, None)
+        .map_err(|e| e.message().to_string())?;
+    let mut index = repo.index().map_err(|e| e.message().to_string())?;
+    if index.has_conflicts() {
+        let count = index.conflicts().map(|c| c.count()).unwrap_or(0);
+        return Err(format!(
+            "Cherry-pick of {} stopped with {} conflicted file(s); resolve and commit, or abort",
+            short_id, count
+        ));
+    }
+    let tree_id = index.write_tree().map_err(|e| e.message().to_string())?;
+    if tree_id == head.tree_id() {
+        repo.cleanup_state().map_err(|e| e.message().to_string())?;
+        return Err(format!("{} is already applied; nothing to cherry-pick", short_id));
+    }
+    let tree = repo.find_tree(tree_id).map_err(|e| e.message().to_string())?;
+    let committer = repo.signature().map_err(|e| e.message().to_string())?;
+    let new_id = repo
+        .commit(
+            Some("HEAD"),
+            &commit.author(),
+            &committer,
+            commit.message().unwrap_or(""),
+            &tree,
+            &[&head],
+        )
+        .map_err(|e| e.message().to_string())?;
+    repo.cleanup_state().map_err(|e| e.message().to_string())?;
+
+    let new_id = new_id.to_string();
+    Ok(format!(
+        "Cherry-picked {} as {} {}",
+        short_id,
+        &new_id[..new_id.len().min(8)],
+        commit.summary().unwrap_or("")
+    ))
+}
+
+/// `git init` the tab's folder so a plain project can start using version control.
+pub(crate) fn perform_init_repo(tab_id: usize, repo_path: PathBuf) -> GitOpSnapshot {
+    let started = Instant::now();
+    let result = Repository::init(&repo_path)
+        .map(|_| format!("Initialized a Git repository in {}", repo_path.display()))
+        .map_err(|e| e.message().to_string());
+
+    perf_log!(
+        "init_repo tab={} path={} ok={} took={}ms",
+        tab_id,
+        repo_path.display(),
+        result.is_ok(),
+        started.elapsed().as_millis()
+    );
+
+    GitOpSnapshot {
+        tab_id,
+        kind: GitOpKind::Init,
+        result,
+    }
+}
+
+/// Append a pattern for an untracked file or directory to the repo's `.gitignore`,
+/// creating it if needed. A path that is already listed is left alone.
+pub(crate) fn perform_add_to_gitignore(tab_id: usize, repo_path: PathBuf, path: String) -> GitOpSnapshot {
+    let started = Instant::now();
+    let result = add_to_gitignore(&repo_path, &path);
+
+    perf_log!(
+        "add_to_gitignore tab={} path={} ok={} took={}ms",
+        tab_id,
+        path,
+        result.is_ok(),
+        started.elapsed().as_millis()
+    );
+
+    GitOpSnapshot {
+        tab_id,
+        kind: GitOpKind::Ignore,
+        result,
+    }
+}
+
+fn add_to_gitignore(repo_path: &std::path::Path, path: &str) -> Result<String, String> {
+    let pattern = gitignore_pattern(path);
+    let gitignore = repo_path.join(".gitignore");
+    let mut content = match std::fs::read_to_string(&gitignore) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.to_string()),
+    };
+    let unanchored = &pattern[1..];
+    if content
+        .lines()
+        .map(str::trim)
+        .any(|line| line == pattern || line == unanchored)
+    {
+        return Ok(format!("{} is already in .gitignore", pattern));
+    }
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(&pattern);
+    content.push('\n');
+    std::fs::write(&gitignore, content).map_err(|e| e.to_string())?;
+    Ok(format!("Added {} to .gitignore", pattern))
+}
+
+/// Changed files whose diff has no lines (mode-only changes, empty new files, ...) as
+/// (path, is_staged), so keyboard review can pass over them.
+pub(crate) fn collect_empty_diffs(tab_id: usize, repo_path: PathBuf) -> EmptyDiffsSnapshot {
+    let started = Instant::now();
+    let paths = empty_diffs(&repo_path).unwrap_or_default();
+
+    perf_log!(
+        "empty_diffs tab={} empty={} took={}ms",
+        tab_id,
+        paths.len(),
+        started.elapsed().as_millis()
+    );
+
+    EmptyDiffsSnapshot { tab_id, paths }
+}
+
+fn empty_diffs(repo_path: &std::path::Path) -> Result<HashSet<(String, bool)>, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+    // Emptiness is judged from the delta headers, so no file content is loaded
+    let mut staged_opts = DiffOptions::new();
+    staged_opts.skip_binary_check(true);
+    let staged = repo.diff_tree_to_index(head_tree.as_ref(), None, Some(&mut staged_opts))?;
+    let mut opts = DiffOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .skip_binary_check(true);
+    let unstaged = repo.diff_index_to_workdir(None, Some(&mut opts))?;
+
+    let empty_blob = git2::Oid::hash_object(git2::ObjectType::Blob, &[])?;
+    let workdir = repo.workdir();
+    let mut empty = HashSet::new();
+    for (diff, is_staged) in [(&staged, true), (&unstaged, false)] {
+        for delta in diff.deltas() {
+            let (old, new) = (delta.old_file(), delta.new_file());
+            let Some(path) = new.path().or_else(|| old.path()) else {
+                continue;
+            };
+            let is_empty = match delta.status() {
+                git2::Delta::Untracked => new.size() == 0,
+                git2::Delta::Added => new.id() == empty_blob,
+                git2::Delta::Deleted => old.id() == empty_blob,
+                // Same content on both sides: only the mode (or name) changed. The
+                // working-tree side isn't hashed up front; hash it only when the size
+                // leaves a chance the content is unchanged.
+                _ if new.id().is_zero() => {
+                    old.size() == new.size()
+                        && workdir
+                            .and_then(|dir| {
+                                git2::Oid::hash_file(git2::ObjectType::Blob, dir.join(path)).ok()
+                            })
+                            .is_some_and(|id| id == old.id())
+                }
+                _ => old.id() == new.id(),
+            };
+            if is_empty {
+                empty.insert((path.to_string_lossy().to_string(), is_staged));
+            }
+        }
+    }
+    Ok(empty)
+}
+
+/// Local branch names for the branch switcher, most recently committed first.
+pub(crate) fn collect_branch_list(tab_id: usize, repo_path: PathBuf) -> BranchListSnapshot {
+    let started = Instant::now();
+    let result = branch_list(&repo_path);
+
+    perf_log!(
+        "branch_list tab={} branches={} took={}ms",
+        tab_id,
+        result.as_ref().map(|branches| branches.len()).unwrap_or(0),
+        started.elapsed().as_millis()
+    );
+
+    BranchListSnapshot { tab_id, result }
+}
+
+fn branch_list(repo_path: &std::path::Path) -> Result<Vec<String>, String> {
+    let repo = Repository::open(repo_path).map_err(|e| e.message().to_string())?;
+    let branches = repo
+        .branches(Some(git2::BranchType::Local))
+        .map_err(|e| e.message().to_string())?;
+    let mut named: Vec<(i64, String)> = branches
+        .flatten()
+        .filter_map(|(branch, _)| {
+            let name = branch.name().ok().flatten()?.to_string();
+            let time = branch
+                .get()
+                .peel_to_commit()
+                .map(|c| c.time().seconds())
+                .unwrap_or(0);
+            Some((time, name))
+        })
+        .collect();
+    named.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    Ok(named.into_iter().map(|(_, name)| name).collect())
+}
+
+/// Switch to a local branch. Like `git switch`, local changes are carried over
+/// unless the branch would overwrite them, in which case nothing changes.
+pub(crate) fn perform_checkout_branch(tab_id: usize, repo_path: PathBuf, name: String) -> GitOpSnapshot {
+    let started = Instant::now();
+    let result = checkout_branch(&repo_path, &name);
+
+    perf_log!(
+        "checkout_branch tab={} branch={} ok={} took={}ms",
+        tab_id,
+        name,
+        result.is_ok(),
+        started.elapsed().as_millis()
+    );
+
+    GitOpSnapshot {
+        tab_id,
+        kind: GitOpKind::Checkout,
+        result,
+    }
+}
+
+fn checkout_branch(repo_path: &std::path::Path, name: &str) -> Result<String, String> {
+    let repo = Repository::open(repo_path).map_err(|e| e.message().to_string())?;
+    if let Some(operation) = RepoOperation::from_state(repo.state()).git_command() {
+        return Err(format!("Finish or abort the {} in progress first", operation));
+    }
+    let branch = repo
+        .find_branch(name, git2::BranchType::Local)
+        .map_err(|e| e.message().to_string())?;
+    let refname = branch
+        .get()
+        .name()
+        .ok_or_else(|| format!("Branch '{}' has an invalid name", name))?
+        .to_string();
+    let commit = branch
+        .get()
+        .peel_to_commit()
+        .map_err(|e| e.message().to_string())?;
+    repo.checkout_tree(
+        commit.as_object(),
+        Some(git2::build::CheckoutBuilder::new().safe()),
+    )
+    .map_err(|e| format!("Cannot switch to '{}': {}", name, e.message()))?;
+    repo.set_head(&refname)
+        .map_err(|e| e.message().to_string())?;
+    Ok(format!("Switched to branch '{}'", name))
+}
+
+/// Back out of an in-progress merge/rebase/cherry-pick/revert with `git <op> --abort`.
+/// Without the git CLI, merges, cherry-picks and reverts fall back to a hard reset to
+/// HEAD plus `cleanup_state`; rebases need the CLI.
+pub(crate) fn perform_abort_operation(
+    tab_id: usize,
+    repo_path: PathBuf,
+    operation: RepoOperation,
+) -> GitOpSnapshot {
+    let started = Instant::now();
+    let result = abort_operation(&repo_path, operation);
+
+    perf_log!(
+        "abort_operation tab={} op={:?} ok={} took={}ms",
+        tab_id,
+        operation,
+        result.is_ok(),
+        started.elapsed().as_millis()
+    );
+
+    GitOpSnapshot {
+        tab_id,
+        kind: GitOpKind::Abort(operation),
+        result,
+    }
+}
+
+fn abort_operation(repo_path: &std::path::Path, operation: RepoOperation) -> Result<String, String> {
+    let Some(command) = operation.git_command() else {
+        return Err("Nothing to abort".to_string());
+    };
+    match std::process::Command::new("git")
+        .args([command, "--abort"])
+        .current_dir(repo_path)
+        .output()
+    {
+        Ok(output) if output.status.success() => Ok(format!("Aborted {}", command)),
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(stderr.trim().lines().last().unwrap_or("git failed").to_string())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            if operation == RepoOperation::Rebase {
+                return Err("Aborting a rebase needs the git CLI".to_string());
+            }
+            let repo = Repository::open(repo_path).map_err(|e| e.message().to_string())?;
+            let head = repo
+                .head()
+                .and_then(|h| h.peel(git2::ObjectType::Commit))
+                .map_err(|e| e.message().to_string())?;
+            repo.reset(&head, git2::ResetType::Hard, None)
+                .map_err(|e| e.message().to_string())?;
+            repo.cleanup_state().map_err(|e| e.message().to_string())?;
+            Ok(format!("Aborted {}", command))
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// The commits being merged in (MERGE_HEAD) while a merge is in progress; they
+/// become the extra parents of the commit that concludes it.
+fn merge_head_commits(
+    repo: &Repository,
+    state: git2::RepositoryState,
+) -> Result<Vec<git2::Commit<'_>>, String> {
+    if state != git2::RepositoryState::Merge {
+        return Ok(Vec::new());
+    }
+    let mut oids = Vec::new();
+    repo.mergehead_foreach(|oid| {
+        oids.push(*oid);
+        true
+    })
+    .map_err(|e| e.message().to_string())?;
+    oids.into_iter()
+        .map(|oid| repo.find_commit(oid).map_err(|e| e.message().to_string()))
+        .collect()
+}
+
+/// Committing the resolved index concludes a conflicted merge, cherry-pick or
+/// revert; drop its state files (MERGE_HEAD, CHERRY_PICK_HEAD, ...) as git does.
+/// Rebases continue from the CLI and are left alone.
+fn conclude_operation(repo: &Repository, state: git2::RepositoryState) -> Result<(), String> {
+    if matches!(
+        state,
+        git2::RepositoryState::Merge
+            | git2::RepositoryState::CherryPick
+            | git2::RepositoryState::Revert
+    ) {
+        repo.cleanup_state().map_err(|e| e.message().to_string())?;
+    }
+    Ok(())
+}
+
+/// Run an external difftool command line through the platform shell in the repo
+/// root, waiting for the tool to close so a failure can be reported.
+pub(crate) fn run_difftool(repo_path: PathBuf, command_line: String) -> Result<(), String> {
+    let started = Instant::now();
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", &command_line]);
+        command
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut command = {
+        let mut command = std::process::Command::new("sh");
+        command.args(["-c", &command_line]);
+        command
+    };
+    let result = match command
+        .current_dir(&repo_path)
+        .stdin(std::process::Stdio::null())
+        .output()
+    {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(stderr
+                .trim()
+                .lines()
+                .last()
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("`{}` failed ({})", command_line, output.status)))
+        }
+        Err(e) => Err(e.to_string()),
+    };
+
+    perf_log!(
+        "difftool ok={} took={}ms",
+        result.is_ok(),
+        started.elapsed().as_millis()
+    );
+    result
+}
+
+fn remote_sync(
+    repo_path: &std::path::Path,
+    kind: GitOpKind,
+    progress: &RemoteProgress,
+) -> Result<String, String> {
+    let repo = Repository::open(repo_path).map_err(|e| e.message().to_string())?;
+    let remote_name = current_remote_name(&repo);
+    if kind == GitOpKind::Push {
+        return push_current_branch(&repo, &remote_name, progress);
+    }
+    fetch_remote(&repo, &remote_name, progress)?;
+    if kind != GitOpKind::Pull {
+        return Ok(format!("Fetched from {}", remote_name));
+    }
+
+    let head = repo.head().map_err(|e| e.message().to_string())?;
+    let branch_name = head.shorthand().unwrap_or("HEAD").to_string();
+    let upstream = repo
+        .find_branch(&branch_name, git2::BranchType::Local)
+        .and_then(|b| b.upstream())
+        .map_err(|_| format!("'{}' has no upstream branch to pull from", branch_name))?;
+    let upstream_commit = repo
+        .reference_to_annotated_commit(upstream.get())
+        .map_err(|e| e.message().to_string())?;
+    let (analysis, _) = repo
+        .merge_analysis(&[&upstream_commit])
+        .map_err(|e| e.message().to_string())?;
+
+    if analysis.is_up_to_date() {
+        return Ok(format!("'{}' is already up to date", branch_name));
+    }
+    if !analysis.is_fast_forward() {
+        return Err(format!(
+            "'{}' has diverged from its upstream; merge or rebase in the terminal",
+            branch_name
+        ));
+    }
+
+    let target = repo
+        .find_object(upstream_commit.id(), None)
+        .map_err(|e| e.message().to_string())?;
+    // Safe checkout refuses to overwrite local modifications
+    let mut checkout = git2::build::CheckoutBuilder::new();
+    checkout.safe();
+    repo.checkout_tree(&target, Some(&mut checkout))
+        .map_err(|e| format!("Fast-forward blocked by local changes: {}", e.message()))?;
+    let mut head_ref = repo.head().map_err(|e| e.message().to_string())?;
+    head_ref
+        .set_target(upstream_commit.id(), "pull: fast-forward")
+        .map_err(|e| e.message().to_string())?;
+
+    let id = upstream_commit.id().to_string();
+    Ok(format!(
+        "Fast-forwarded '{}' to {}",
+        branch_name,
+        &id[..id.len().min(8)]
+    ))
+}
+
+fn push_current_branch(
+    repo: &Repository,
+    remote_name: &str,
+    progress: &RemoteProgress,
+) -> Result<String, String> {
+    let head = repo.head().map_err(|e| e.message().to_string())?;
+    if !head.is_branch() {
+        return Err("HEAD is detached; check out a branch to push".to_string());
+    }
+    let branch_name = head.shorthand().unwrap_or("HEAD").to_string();
+    let config = repo.config().ok();
+
+    // Push to the configured merge ref, or to a same-named branch on first push
+    let merge_ref = config
+        .as_ref()
+        .and_then(|c| c.get_string(&format!("branch.{}.merge", branch_name)).ok());
+    let set_upstream = merge_ref.is_none();
+    let remote_ref = merge_ref.unwrap_or_else(|| format!("refs/heads/{}", branch_name));
+    let refspec = format!("refs/heads/{}:{}", branch_name, remote_ref);
+
+    let mut remote = repo
+        .find_remote(remote_name)
+        .map_err(|e| remote_error_message(remote_name, &e))?;
+    let rejection = std::cell::RefCell::new(None::<String>);
+    let mut callbacks = remote_callbacks(config);
+    track_remote_progress(&mut callbacks, progress);
+    callbacks.push_update_reference(|refname, status| {
+        if let Some(status) = status {
+            *rejection.borrow_mut() = Some(format!("{}: {}", refname, status));
+        }
+        Ok(())
+    });
+    let mut push_opts = git2::PushOptions::new();
+    push_opts.remote_callbacks(callbacks);
+
+    if let Err(err) = remote.push(&[refspec.as_str()], Some(&mut push_opts)) {
+        if err.code() == git2::ErrorCode::NotFastForward {
+            return Err(format!(
+                "Push rejected: '{}' is behind {}. Pull first; force-push is not supported here.",
+                branch_name, remote_name
+            ));
+        }
+        return Err(remote_error_message(remote_name, &err));
+    }
+    drop(push_opts);
+    if let Some(reason) = rejection.into_inner() {
+        return Err(format!("Push rejected by {}: {}", remote_name, reason));
+    }
+
+    if set_upstream {
+        let upstream = format!(
+            "{}/{}",
+            remote_name,
+            remote_ref.trim_start_matches("refs/heads/")
+        );
+        if let Ok(mut branch) = repo.find_branch(&branch_name, git2::BranchType::Local) {
+            branch
+                .set_upstream(Some(&upstream))
+                .map_err(|e| format!("Pushed, but setting upstream failed: {}", e.message()))?;
+        }
+        return Ok(format!("Pushed '{}' and set upstream to {}", branch_name, upstream));
+    }
+    Ok(format!("Pushed '{}' to {}", branch_name, remote_name))
+}
+
+/// Load a file for the viewer. `view_source` reads Markdown/HTML as plain text
+/// instead of preparing their rendered webview content.
+pub(crate) fn collect_file_load(
+    tab_id: usize,
+    path: PathBuf,
+    is_dark_theme: bool,
+    view_source: bool,
+) -> FileLoadSnapshot {
+    let started = Instant::now();
+    let mut snapshot = FileLoadSnapshot {
+        tab_id,
+        path: path.clone(),
+        file_content: String::new(),
+        image_path: None,
+        webview_content: None,
+        file_preview_notice: None,
+        syntax_highlight_lines: None,
+        syntax_highlight_notice: None,
+        file_signature: None,
+        load_error: None,
+        symlink_target: None,
+        first_line: 1,
+    };
+
+    if std::fs::symlink_metadata(&path).is_ok_and(|m| m.file_type().is_symlink()) {
+        snapshot.symlink_target = std::fs::canonicalize(&path).ok();
+    }
+
+    let file_metadata = std::fs::metadata(&path).ok();
+    let file_size = file_metadata.as_ref().map(|m| m.len()).unwrap_or(0);
+    let limits = file_load_limits();
+    snapshot.file_signature = file_metadata.as_ref().and_then(|metadata| {
+        let modified_unix_nanos = metadata
+            .modified()
+            .ok()?
+            .duration_since(UNIX_EPOCH)
+            .ok()?
+            .as_nanos();
+        Some(FileVersionSignature {
+            modified_unix_nanos,
+            file_len: metadata.len(),
+        })
+    });
+
+    #[cfg(feature = "excalidraw")]
+    if excalidraw::is_excalidraw_file(&path) {
+        if file_size > limits.inline_webview_max_bytes {
+            snapshot.file_preview_notice = Some(format!(
+                "Inline preview skipped for large Excalidraw file ({}). Click \"View in Browser\".",
+                format_bytes(file_size)
+            ));
+            perf_log!(
+                "file_load tab={} path={} kind=excalidraw_skip size={}B text={}B webview={}B notice={} took={}ms",
+                tab_id,
+                path.display(),
+                file_size,
+                snapshot.file_content.len(),
+                snapshot.webview_content.as_ref().map(|s| s.len()).unwrap_or(0),
+                snapshot.file_preview_notice.is_some(),
+                started.elapsed().as_millis()
+            );
+            return snapshot;
+        }
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            if excalidraw::validate_excalidraw(&content) {
+                snapshot.webview_content =
+                    Some(excalidraw::render_excalidraw_html(&content, is_dark_theme));
+            }
+        }
+        perf_log!(
+            "file_load tab={} path={} kind=excalidraw_inline size={}B text={}B webview={}B notice={} took={}ms",
+            tab_id,
+            path.display(),
+            file_size,
+            snapshot.file_content.len(),
+            snapshot.webview_content.as_ref().map(|s| s.len()).unwrap_or(0),
+            snapshot.file_preview_notice.is_some(),
+            started.elapsed().as_millis()
+        );
+        return snapshot;
+    }
+
+    if !view_source && TabState::is_markdown_file(&path) {
+        if file_size > limits.inline_webview_max_bytes {
+            snapshot.file_preview_notice = Some(format!(
+                "Inline preview skipped for large Markdown file ({}). Click \"View in Browser\".",
+                format_bytes(file_size)
+            ));
+            perf_log!(
+                "file_load tab={} path={} kind=markdown_skip size={}B text={}B webview={}B notice={} took={}ms",
+                tab_id,
+                path.display(),
+                file_size,
+                snapshot.file_content.len(),
+                snapshot.webview_content.as_ref().map(|s| s.len()).unwrap_or(0),
+                snapshot.file_preview_notice.is_some(),
+                started.elapsed().as_millis()
+            );
+            return snapshot;
+        }
+        if let Some(content) = read_file_text(&path, &mut snapshot.load_error) {
+            snapshot.webview_content = Some(markdown::render_markdown_to_html(
+                &content,
+                is_dark_theme,
+                &markdown_render(),
+            ));
+        }
+    } else if !view_source && TabState::is_html_file(&path) {
+        if file_size > limits.inline_webview_max_bytes {
+            snapshot.file_preview_notice = Some(format!(
+                "Inline preview skipped for large HTML file ({}). Click \"View in Browser\".",
+                format_bytes(file_size)
+            ));
+            perf_log!(
+                "file_load tab={} path={} kind=html_skip size={}B text={}B webview={}B notice={} took={}ms",
+                tab_id,
+                path.display(),
+                file_size,
+                snapshot.file_content.len(),
+                snapshot.webview_content.as_ref().map(|s| s.len()).unwrap_or(0),
+                snapshot.file_preview_notice.is_some(),
+                started.elapsed().as_millis()
+            );
+            return snapshot;
+        }
+        if let Some(content) = read_file_text(&path, &mut snapshot.load_error) {
+            snapshot.webview_content = Some(content);
+        }
+    } else if TabState::is_image_file(&path) {
+        snapshot.image_path = Some(path.clone());
+    } else if open_mode(&path) == Some(OpenMode::Tail) {
+        // Only the end is shown, so there's no need to read the rest however large
+        let max_lines = limits.preview_max_lines.min(MAX_FILE_VIEW_RENDER_LINES);
+        match read_text_tail(&path, limits.preview_max_bytes, max_lines) {
+            Ok((tail, first_line, truncated)) => {
+                if truncated {
+                    snapshot.file_preview_notice = Some(format!(
+                        "Following the end of {}: showing the last {} lines.",
+                        format_bytes(file_size),
+                        tail.lines().count()
+                    ));
+                }
+                snapshot.file_content = tail;
+                snapshot.first_line = first_line;
+            }
+            Err(e) => snapshot.load_error = Some(e.to_string()),
+        }
+    } else if file_size > limits.full_text_max_bytes {
+        if let Ok(preview) =
+            read_text_preview(&path, limits.preview_max_bytes, limits.preview_max_lines)
+        {
+            snapshot.file_content = preview;
+        } else if let Ok(content) = std::fs::read_to_string(&path) {
+            snapshot.file_content = content;
+        }
+        snapshot.file_preview_notice = Some(format!(
+            "Large file ({}): showing first {} lines (~{} KB).",
+            format_bytes(file_size),
+            limits.preview_max_lines,
+            limits.preview_max_bytes / 1024
+        ));
+    } else if let Some(content) = read_file_text(&path, &mut snapshot.load_error) {
+        snapshot.file_content = content;
+    }
+
+    let kind = if snapshot.image_path.is_some() {
+        "image"
+    } else if snapshot.webview_content.is_some() {
+        "inline_webview"
+    } else if snapshot.file_preview_notice.is_some() {
+        "text_preview"
+    } else {
+        "text"
+    };
+    perf_log!(
+        "file_load tab={} path={} kind={} size={}B text={}B webview={}B preview_notice={} syntax_notice={} took={}ms",
+        tab_id,
+        path.display(),
+        kind,
+        file_size,
+        snapshot.file_content.len(),
+        snapshot
             .webview_content
             .as_ref()
             .map(|s| s.len())
@@ -637,6 +2544,104 @@ pub(crate) fn collect_file_load(
     snapshot
 }
 
+/// Diff two arbitrary files for compare mode, within the file viewer's limits: past
+/// `full_text_max_bytes` only each file's previewed head is compared, and binary files
+/// aren't compared at all.
+pub(crate) fn collect_file_compare(
+    tab_id: usize,
+    left: PathBuf,
+    right: PathBuf,
+) -> CompareSnapshot {
+    let started = Instant::now();
+    let limits = file_load_limits();
+    let mut snapshot = CompareSnapshot {
+        tab_id,
+        left,
+        right,
+        lines: Vec::new(),
+        diff_syntax_lines: None,
+        notice: None,
+    };
+
+    let texts = read_compare_text(&snapshot.left, &limits)
+        .and_then(|old| Ok((old, read_compare_text(&snapshot.right, &limits)?)));
+    match texts {
+        Ok(((old, old_truncated), (new, new_truncated))) => {
+            snapshot.lines = text_diff_lines(&old, &new);
+            snapshot.notice = if old_truncated || new_truncated {
+                Some(format!(
+                    "Large file: comparing the first {} lines (~{} KB).",
+                    limits.preview_max_lines,
+                    limits.preview_max_bytes / 1024
+                ))
+            } else if snapshot.lines.is_empty() {
+                Some("The files are identical.".to_string())
+            } else {
+                None
+            };
+        }
+        Err(err) => snapshot.notice = Some(err),
+    }
+
+    perf_log!(
+        "file_compare tab={} left={} right={} lines={} took={}ms",
+        tab_id,
+        snapshot.left.display(),
+        snapshot.right.display(),
+        snapshot.lines.len(),
+        started.elapsed().as_millis()
+    );
+    snapshot
+}
+
+/// A compare side's text, and whether it was cut down to the preview limits.
+fn read_compare_text(
+    path: &std::path::Path,
+    limits: &crate::config::FileLoadLimits,
+) -> Result<(String, bool), String> {
+    use std::io::Read;
+
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let describe = |e: std::io::Error| format!("Couldn't read {}: {}", name, e);
+    let size = std::fs::metadata(path).map_err(describe)?.len();
+    let truncated = size > limits.full_text_max_bytes;
+    let bytes = if truncated {
+        let mut bytes = Vec::new();
+        std::fs::File::open(path)
+            .and_then(|file| file.take(limits.preview_max_bytes as u64).read_to_end(&mut bytes))
+            .map_err(describe)?;
+        bytes
+    } else {
+        std::fs::read(path).map_err(describe)?
+    };
+    // Same binary sniff as git: a NUL in the first 8K
+    if bytes.iter().take(8000).any(|b| *b == 0) {
+        return Err(format!("{} is a binary file; only text files can be compared.", name));
+    }
+
+    let text = String::from_utf8_lossy(&bytes);
+    if truncated {
+        let head: Vec<&str> = text.lines().take(limits.preview_max_lines).collect();
+        Ok((head.join("\n"), true))
+    } else {
+        Ok((text.into_owned(), false))
+    }
+}
+
+/// Read a file as text, recording why it couldn't be read. Non-UTF-8 content isn't
+/// an error; the viewer just shows nothing for it.
+fn read_file_text(path: &std::path::Path, load_error: &mut Option<String>) -> Option<String> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Some(content),
+        Err(e) => {
+            if e.kind() != std::io::ErrorKind::InvalidData {
+                *load_error = Some(e.to_string());
+            }
+            None
+        }
+    }
+}
+
 pub(crate) fn collect_file_syntax_highlight(
     tab_id: usize,
     path: PathBuf,
@@ -656,7 +2661,7 @@ pub(crate) fn collect_file_syntax_highlight(
             .join("\n")
     };
     let (syntax_highlight_lines, syntax_highlight_notice) =
-        if content_prefix.trim().is_empty() || TabState::is_markdown_file(&path) {
+        if content_prefix.trim().is_empty() {
             (None, None)
         } else {
             build_syntax_highlight_lines(&path, &content_prefix, is_dark_theme)
@@ -17,6 +17,63 @@ impl AppTheme {
             AppTheme::Light => AppTheme::Dark,
         }
     }
+
+    /// The desktop's light/dark preference, for `theme = "system"`. `None` when the
+    /// platform doesn't say.
+    pub fn detect_system() -> Option<Self> {
+        #[cfg(target_os = "macos")]
+        {
+            // The key only exists in dark mode
+            let output = std::process::Command::new("defaults")
+                .args(["read", "-g", "AppleInterfaceStyle"])
+                .output()
+                .ok()?;
+            let dark = output.status.success()
+                && String::from_utf8_lossy(&output.stdout).trim() == "Dark";
+            Some(if dark { AppTheme::Dark } else { AppTheme::Light })
+        }
+        #[cfg(target_os = "linux")]
+        {
+            let output = std::process::Command::new("gsettings")
+                .args(["get", "org.gnome.desktop.interface", "color-scheme"])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())?;
+            let scheme = String::from_utf8_lossy(&output.stdout);
+            if scheme.contains("dark") {
+                Some(AppTheme::Dark)
+            } else if scheme.contains("light") || scheme.contains("default") {
+                Some(AppTheme::Light)
+            } else {
+                None
+            }
+        }
+        #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+        {
+            None
+        }
+    }
+}
+
+/// Backgrounds for added/deleted diff lines and their changed words, as painted by
+/// the diff panel and the HTML export. Config overrides may replace the theme's.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffColors {
+    pub add_bg: Color,
+    pub del_bg: Color,
+    pub add_highlight: Color,
+    pub del_highlight: Color,
+}
+
+/// `#rrggbb` or `#rrggbbaa` (the `#` is optional).
+pub fn parse_hex_color(value: &str) -> Option<Color> {
+    let hex = value.trim().trim_start_matches('#');
+    if !matches!(hex.len(), 6 | 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |idx: usize| u8::from_str_radix(&hex[idx..idx + 2], 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some(Color::from_rgba8(channel(0)?, channel(2)?, channel(4)?, alpha as f32 / 255.0))
 }
 
 // Theme color functions - complete Catppuccin palette for future use
@@ -534,6 +591,15 @@ impl AppTheme {
         }
     }
 
+    pub fn diff_colors(&self) -> DiffColors {
+        DiffColors {
+            add_bg: self.diff_add_bg(),
+            del_bg: self.diff_del_bg(),
+            add_highlight: self.diff_add_highlight(),
+            del_highlight: self.diff_del_highlight(),
+        }
+    }
+
     pub fn bg_crust(&self) -> Color {
         match self {
             AppTheme::Dark => color!(0x11111b),
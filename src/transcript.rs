@@ -0,0 +1,198 @@
+//! Plain-text transcripts of agent tabs (tabs started with a command such as `claude`
+//! or `codex`), written to `~/.config/gitterm/transcripts/<date>-<repo>-<tab>.log`.
+//!
+//! Terminals only expose their whole scrollback, so each capture works out which lines
+//! are new since the last one. The bottom of the screen is held back until the tab
+//! closes because agents redraw their prompt area in place.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Lines at the bottom of a capture that may still be redrawn.
+const HOLD_BACK_LINES: usize = 40;
+/// Lines remembered from the previous capture to find where the next one resumes.
+const TAIL_LINES: usize = 200;
+
+pub fn transcripts_dir() -> PathBuf {
+    let instance_dir = crate::config::instance_config_dir();
+    instance_dir
+        .parent()
+        .unwrap_or(&instance_dir)
+        .join("transcripts")
+}
+
+pub fn transcript_path(date: chrono::NaiveDate, repo_name: &str, tab_id: usize) -> PathBuf {
+    let repo: String = repo_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    transcripts_dir().join(format!("{}-{}-{}.log", date.format("%Y-%m-%d"), repo, tab_id))
+}
+
+#[derive(Debug)]
+pub struct Transcript {
+    pub path: PathBuf,
+    // Written before the first captured line
+    header: Option<String>,
+    tail: Vec<String>,
+    // Sequence number of the newest capture taken
+    last_capture: u64,
+}
+
+impl Transcript {
+    pub fn new(path: PathBuf, header: String) -> Self {
+        Self {
+            path,
+            header: Some(header),
+            tail: Vec::new(),
+            last_capture: 0,
+        }
+    }
+
+    /// `take_new_lines` for capture number `seq`. Captures are processed off the UI
+    /// thread, so one older than a capture already taken is dropped.
+    pub fn take_capture(&mut self, seq: u64, text: &str, flush: bool) -> Vec<String> {
+        if seq <= self.last_capture {
+            return Vec::new();
+        }
+        self.last_capture = seq;
+        self.take_new_lines(text, flush)
+    }
+
+    /// Lines of `text` (the terminal's full contents, ANSI already stripped) not
+    /// written yet. `flush` also takes the held-back bottom of the screen.
+    pub fn take_new_lines(&mut self, text: &str, flush: bool) -> Vec<String> {
+        let mut lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+        let start = self.resume_index(&lines);
+        let end = if flush {
+            lines.len()
+        } else {
+            lines.len().saturating_sub(HOLD_BACK_LINES)
+        };
+        if start >= end {
+            return Vec::new();
+        }
+
+        let new_lines: Vec<String> = lines[start..end].iter().map(|l| l.to_string()).collect();
+        self.tail.extend(new_lines.iter().cloned());
+        if self.tail.len() > TAIL_LINES {
+            self.tail.drain(..self.tail.len() - TAIL_LINES);
+        }
+        match self.header.take() {
+            Some(header) => std::iter::once(header).chain(new_lines).collect(),
+            None => new_lines,
+        }
+    }
+
+    /// Index of the first line after the last written ones. Scans from the bottom
+    /// for the remembered tail; near the top only the part of the tail that hasn't
+    /// scrolled out of the scrollback has to match. No match means the terminal was
+    /// cleared and everything is new.
+    fn resume_index(&self, lines: &[&str]) -> usize {
+        let Some(last) = self.tail.last() else {
+            return 0;
+        };
+        for end in (0..lines.len()).rev() {
+            if lines[end] != last {
+                continue;
+            }
+            let n = (end + 1).min(self.tail.len());
+            let matches = lines[end + 1 - n..=end]
+                .iter()
+                .zip(&self.tail[self.tail.len() - n..])
+                .all(|(a, b)| a == b);
+            if matches {
+                return end + 1;
+            }
+        }
+        0
+    }
+}
+
+/// Append lines to a transcript, first moving a file that would grow past `max_bytes`
+/// to `<name>.log.1` (replacing the previous one).
+pub fn append(path: &Path, lines: &[String], max_bytes: u64) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let chunk: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+    let existing = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    if existing > 0 && existing + chunk.len() as u64 > max_bytes {
+        std::fs::rename(path, path.with_extension("log.1"))?;
+    }
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(chunk.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered(range: std::ops::Range<usize>) -> String {
+        range.map(|i| format!("line {}\n", i)).collect()
+    }
+
+    #[test]
+    fn test_take_new_lines_holds_back_screen_and_resumes() {
+        let mut transcript = Transcript::new(PathBuf::from("t.log"), "# header".to_string());
+        let first = transcript.take_new_lines(&numbered(0..50), false);
+        assert_eq!(first.len(), 1 + 10);
+        assert_eq!(first[0], "# header");
+        assert_eq!(first[10], "line 9");
+
+        // Nothing new past the held-back screen
+        assert!(transcript.take_new_lines(&numbered(0..50), false).is_empty());
+
+        // Old lines scrolled out of the scrollback; resume after the last written line
+        let next = transcript.take_new_lines(&numbered(5..70), false);
+        assert_eq!(next.first().map(String::as_str), Some("line 10"));
+        assert_eq!(next.last().map(String::as_str), Some("line 29"));
+
+        let rest = transcript.take_new_lines(&format!("{}\n\n", numbered(5..70)), true);
+        assert_eq!(rest.len(), 40);
+        assert_eq!(rest.last().map(String::as_str), Some("line 69"));
+
+        // A cleared terminal starts over
+        let cleared = transcript.take_new_lines("fresh\n", true);
+        assert_eq!(cleared, vec!["fresh".to_string()]);
+    }
+
+    #[test]
+    fn test_take_capture_drops_older_captures() {
+        let mut transcript = Transcript::new(PathBuf::from("t.log"), "# header".to_string());
+        assert_eq!(
+            transcript.take_capture(2, &numbered(0..3), true).len(),
+            1 + 3
+        );
+        // Capture 1 was read before capture 2 but finished after it
+        assert!(transcript.take_capture(1, &numbered(0..1), true).is_empty());
+        let next = transcript.take_capture(3, &numbered(0..4), true);
+        assert_eq!(next, vec!["line 3".to_string()]);
+    }
+
+    #[test]
+    fn test_append_rotates_past_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sub").join("t.log");
+        append(&path, &["a".repeat(30)], 64).unwrap();
+        append(&path, &["b".repeat(30)], 64).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 62);
+
+        append(&path, &["c".repeat(10)], 64).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), format!("{}\n", "c".repeat(10)));
+        let rotated = std::fs::read_to_string(path.with_extension("log.1")).unwrap();
+        assert!(rotated.starts_with(&"a".repeat(30)));
+    }
+}
@@ -3,7 +3,7 @@
 // Note: Due to threading constraints (wry's WebView is not Send/Sync),
 // the WebView must be created and managed on the main thread.
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 
 type WebViewBounds = (f32, f32, f32, f32);
 use wry::raw_window_handle::{HasWindowHandle, WindowHandle};
@@ -12,6 +12,19 @@ use wry::{Rect, WebView, WebViewBuilder};
 thread_local! {
     static WEBVIEW: RefCell<Option<WebView>> = const { RefCell::new(None) };
     static PENDING_HTML: RefCell<Option<(String, WebViewBounds)>> = const { RefCell::new(None) };
+    /// Heading at the top of the rendered Markdown, posted by its scroll-sync script
+    static VISIBLE_HEADING: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Heading the preview is scrolled to, numbered like `markdown::heading_lines`
+pub fn visible_heading() -> Option<usize> {
+    VISIBLE_HEADING.with(Cell::get)
+}
+
+fn record_ipc_message(message: &str) {
+    if let Some(heading) = message.strip_prefix("heading:") {
+        VISIBLE_HEADING.with(|visible| visible.set(heading.parse().ok()));
+    }
 }
 
 /// Wrapper that holds a raw window handle and implements HasWindowHandle
@@ -68,6 +81,7 @@ pub fn try_create_with_window(window: &dyn HasWindowHandle) -> Result<(), String
                         height as f64,
                     )),
                 });
+                VISIBLE_HEADING.with(|visible| visible.set(None));
                 webview
                     .load_html(&html)
                     .map_err(|e| format!("Failed to load HTML: {}", e))?;
@@ -88,6 +102,7 @@ pub fn try_create_with_window(window: &dyn HasWindowHandle) -> Result<(), String
                     )),
                 })
                 .with_html(&html)
+                .with_ipc_handler(|request| record_ipc_message(request.body()))
                 .with_transparent(false)
                 .build_as_child(&wrapper)
                 .map_err(|e| format!("Failed to create WebView: {}", e))?;
@@ -121,6 +136,7 @@ pub fn update_bounds(x: f32, y: f32, width: f32, height: f32) {
 pub fn update_content(html: &str) {
     WEBVIEW.with(|wv| {
         if let Some(webview) = wv.borrow().as_ref() {
+            VISIBLE_HEADING.with(|visible| visible.set(None));
             let _ = webview.load_html(html);
         }
     });